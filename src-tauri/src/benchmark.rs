@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::metrics::{pause_history, BackendMetrics};
+use crate::operations::{Operation, OperationError};
+use crate::recipes::{arg_bool, arg_str, run_step};
+use crate::text_diff;
+use crate::text_processor::TextProcessor;
+
+const WORDS: [&str; 16] = [
+    "error", "warning", "info", "debug", "request", "response", "user", "session",
+    "timeout", "connection", "GET", "POST", "/api/v1/items", "200", "404", "500",
+];
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRequest {
+    pub operation: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    /// Eigener Text; wenn leer, wird ein Korpus nach `corpus` generiert
+    #[serde(default)]
+    pub input: Option<String>,
+    #[serde(default)]
+    pub corpus: CorpusSpec,
+    pub iterations: usize,
+    #[serde(default)]
+    pub warmup: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CorpusSpec {
+    pub lines: usize,
+    pub words_per_line: usize,
+    /// Anteil (0.0 - 1.0) der Zeilen, die eine frühere Zeile wiederholen
+    pub duplicate_ratio: f64,
+    pub seed: u64,
+}
+
+impl Default for CorpusSpec {
+    fn default() -> Self {
+        Self {
+            lines: 10_000,
+            words_per_line: 8,
+            duplicate_ratio: 0.1,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub mean: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub operation: String,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub input_bytes: usize,
+    pub input_lines: usize,
+    pub time_ms: Summary,
+    pub peak_memory_bytes: Summary,
+    pub runs: Vec<BackendMetrics>,
+}
+
+/// Führt die Operation `warmup + iterations` mal aus und fasst Zeit und Speicher der gemessenen Läufe zusammen.
+/// Die Läufe landen nicht in der Metrik-Historie.
+pub fn run_benchmark(request: &BenchmarkRequest, op: &Operation) -> Result<BenchmarkResult, OperationError> {
+    if request.iterations == 0 {
+        return Err("⚠ Mindestens eine Iteration ist nötig".to_string().into());
+    }
+
+    let _pause = pause_history();
+    let input = match &request.input {
+        Some(text) if !text.is_empty() => text.clone(),
+        _ => generate_corpus(&request.corpus),
+    };
+
+    let total = request.warmup + request.iterations;
+    let mut runs = Vec::with_capacity(request.iterations);
+    for i in 0..total {
+        op.progress(i, total)?;
+        let metrics = run_once(&request.operation, &request.args, &input)?;
+        if i >= request.warmup {
+            runs.push(metrics);
+        }
+    }
+
+    let times: Vec<f64> = runs.iter().map(|m| m.execution_time_ms).collect();
+    let memory: Vec<f64> = runs.iter().map(|m| m.peak_alloc_bytes as f64).collect();
+
+    Ok(BenchmarkResult {
+        operation: request.operation.clone(),
+        iterations: request.iterations,
+        warmup: request.warmup,
+        input_bytes: input.len(),
+        input_lines: input.lines().count(),
+        time_ms: summarize(times),
+        peak_memory_bytes: summarize(memory),
+        runs,
+    })
+}
+
+fn run_once(operation: &str, args: &Map<String, Value>, input: &str) -> Result<BackendMetrics, OperationError> {
+    let untracked = Operation::untracked();
+    Ok(match operation {
+        "split" => {
+            TextProcessor::process_text_block_to_column_strings(
+                input,
+                arg_str(args, "delimiter"),
+                arg_bool(args, "trimparts"),
+                arg_bool(args, "useregex"),
+                &untracked,
+            )?
+            .metrics
+        }
+        // Ohne zweiten Text wird gegen die umgekehrte Eingabe verglichen
+        "diff" => {
+            let newtext = match arg_str(args, "newtext") {
+                "" => input.lines().rev().collect::<Vec<_>>().join("\n"),
+                text => text.to_string(),
+            };
+            text_diff::get_diff_json(input, &newtext, arg_bool(args, "ignorewhitespace"), None, &untracked)?.metrics
+        }
+        _ => run_step(input, operation, args, &untracked)?.metrics,
+    })
+}
+
+fn summarize(mut values: Vec<f64>) -> Summary {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f64| {
+        let rank = ((p * values.len() as f64).ceil() as usize).clamp(1, values.len());
+        values[rank - 1]
+    };
+
+    Summary {
+        min: values[0],
+        median: percentile(0.5),
+        p95: percentile(0.95),
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+    }
+}
+
+/// Erzeugt reproduzierbaren Log-ähnlichen Text (xorshift, damit kein `rand` nötig ist).
+pub fn generate_corpus(spec: &CorpusSpec) -> String {
+    let mut state = spec.seed.max(1);
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut lines: Vec<String> = Vec::with_capacity(spec.lines);
+    for i in 0..spec.lines {
+        let repeat = i > 0 && (next() % 1000) as f64 / 1000.0 < spec.duplicate_ratio;
+        if repeat {
+            let previous = lines[(next() % i as u64) as usize].clone();
+            lines.push(previous);
+            continue;
+        }
+
+        let words: Vec<String> = (0..spec.words_per_line)
+            .map(|_| match next() % 4 {
+                0 => (next() % 100_000).to_string(),
+                _ => WORDS[(next() % WORDS.len() as u64) as usize].to_string(),
+            })
+            .collect();
+        lines.push(words.join(" "));
+    }
+    lines.join("\n")
+}
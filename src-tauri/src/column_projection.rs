@@ -0,0 +1,216 @@
+use crate::format_conversion::{self, ConversionOptions, Table};
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+/// Transponieren ist nur für überschaubare Matrizen gedacht.
+const MAX_TRANSPOSE_CELLS: usize = 250_000;
+
+/// Löst eine Spaltenangabe ähnlich wie bei `cut` auf: `1,3,5-7`, offene Bereiche bis zum Ende (`4-`),
+/// negative Indizes (`-1` = letzte Spalte, `-3` = drittletzte, `2--2`) und Spaltennamen. Anders als bei `cut`
+/// ist `-3` also kein Bereich; "bis Spalte 3" wird als `1-3` geschrieben. Absteigende Bereiche kehren die
+/// Reihenfolge um, mehrfach genannte Spalten werden dupliziert. Ergebnis sind 0-basierte Indizes.
+pub fn parse_column_spec(spec: &str, headers: &[String]) -> Result<Vec<usize>, String> {
+    let count = headers.len();
+    let mut indices = Vec::new();
+
+    for item in spec.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+        if let Some(index) = find_header(item, headers) {
+            indices.push(index);
+            continue;
+        }
+        if let Some(index) = parse_index(item, count)? {
+            indices.push(index);
+            continue;
+        }
+
+        let Some((start, end)) = split_range(item) else {
+            return Err(format!("⚠ Unbekannte Spalte '{}'", item));
+        };
+        let start = if start.is_empty() { 0 } else { resolve_bound(start, item, count)? };
+        let end = if end.is_empty() {
+            count.saturating_sub(1)
+        } else {
+            resolve_bound(end, item, count)?
+        };
+        if start <= end {
+            indices.extend(start..=end);
+        } else {
+            indices.extend((end..=start).rev());
+        }
+    }
+
+    if indices.is_empty() {
+        return Err("⚠ Keine Spalten ausgewählt".to_string());
+    }
+    Ok(indices)
+}
+
+fn find_header(item: &str, headers: &[String]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|h| h == item)
+        .or_else(|| headers.iter().position(|h| h.eq_ignore_ascii_case(item)))
+}
+
+/// Einzelner Index (1-basiert oder negativ vom Ende); `None`, wenn `item` keine Zahl ist.
+fn parse_index(item: &str, count: usize) -> Result<Option<usize>, String> {
+    match item.parse::<i64>() {
+        Ok(number) => resolve(number, item, count).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+fn resolve_bound(bound: &str, item: &str, count: usize) -> Result<usize, String> {
+    let number = bound.parse::<i64>().map_err(|_| format!("⚠ Ungültiger Spaltenbereich '{}'", item))?;
+    resolve(number, item, count)
+}
+
+fn resolve(number: i64, item: &str, count: usize) -> Result<usize, String> {
+    let index = match number {
+        0 => return Err(format!("⚠ Spalten werden ab 1 gezählt ('{}')", item)),
+        n if n > 0 => n as usize - 1,
+        n => match count.checked_sub(n.unsigned_abs() as usize) {
+            Some(index) => index,
+            None => return Err(format!("⚠ Spalte {} existiert nicht (nur {} Spalten)", item, count)),
+        },
+    };
+    if index >= count {
+        return Err(format!("⚠ Spalte {} existiert nicht (nur {} Spalten)", item, count));
+    }
+    Ok(index)
+}
+
+/// Trennt `a-b` am Bindestrich, der nicht Vorzeichen ist (`-3`, `2--1`, `4-`).
+fn split_range(item: &str) -> Option<(&str, &str)> {
+    let offset = usize::from(item.starts_with('-'));
+    let position = item[offset..].find('-')? + offset;
+    Some((&item[..position], &item[position + 1..]))
+}
+
+fn write_rows(table: &Table, options: &ConversionOptions) -> String {
+    let mut out = Vec::with_capacity(table.row_count() + 1);
+    if options.header {
+        out.push(format_conversion::join_delimited(
+            table.headers.iter().map(String::as_str),
+            &options.output_delimiter,
+            options.quoted,
+        ));
+    }
+    for i in 0..table.row_count() {
+        out.push(format_conversion::join_delimited(table.row(i), &options.output_delimiter, options.quoted));
+    }
+    out.join("\n")
+}
+
+/// Wählt, entfernt (`drop`), sortiert um oder dupliziert Spalten und schreibt sie mit `output_delimiter` zurück.
+pub fn project_columns(
+    text: &str,
+    spec: &str,
+    drop: bool,
+    options: &ConversionOptions,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("project_columns", text.len());
+
+    let table = format_conversion::parse_table(text, options, op)?;
+    let selected = parse_column_spec(spec, &table.headers)?;
+    collector.phase("parse");
+
+    let indices: Vec<usize> = if drop {
+        (0..table.headers.len()).filter(|i| !selected.contains(i)).collect()
+    } else {
+        selected
+    };
+    let removed_columns = table.headers.len().saturating_sub(indices.len());
+    let projected = Table {
+        headers: indices.iter().map(|&i| table.headers[i].clone()).collect(),
+        columns: indices.iter().map(|&i| table.columns[i].clone()).collect(),
+    };
+    collector.phase("process");
+
+    op.check()?;
+    let result_text = write_rows(&projected, options);
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: removed_columns,
+        metrics,
+    })
+}
+
+/// Vertauscht Zeilen und Spalten; eine Kopfzeile wird dabei zur ersten Spalte.
+pub fn transpose(text: &str, options: &ConversionOptions, op: &Operation) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("transpose", text.len());
+
+    let table = format_conversion::parse_table(text, options, op)?;
+    let rows = table.row_count() + usize::from(options.header);
+    if rows * table.columns.len() > MAX_TRANSPOSE_CELLS {
+        return Err(format!(
+            "⚠ Zu groß zum Transponieren ({} × {} Zellen, maximal {})",
+            rows,
+            table.columns.len(),
+            MAX_TRANSPOSE_CELLS
+        )
+        .into());
+    }
+    collector.phase("parse");
+
+    let mut lines = Vec::with_capacity(table.columns.len());
+    for (index, (header, column)) in table.headers.iter().zip(&table.columns).enumerate() {
+        op.progress(index, table.columns.len())?;
+        let cells = options.header.then_some(header.as_str()).into_iter().chain(column.iter().map(String::as_str));
+        lines.push(format_conversion::join_delimited(cells, &options.output_delimiter, options.quoted));
+    }
+    collector.phase("process");
+
+    let result_text = lines.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn column_spec_indices_and_ranges() {
+        let h = headers(&["a", "b", "c", "d", "e"]);
+        assert_eq!(parse_column_spec("1,3,5", &h).unwrap(), vec![0, 2, 4]);
+        assert_eq!(parse_column_spec("2-4", &h).unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_column_spec("4-", &h).unwrap(), vec![3, 4]);
+        assert_eq!(parse_column_spec("4-2", &h).unwrap(), vec![3, 2, 1]);
+        assert_eq!(parse_column_spec("1,1", &h).unwrap(), vec![0, 0]);
+    }
+
+    #[test]
+    fn column_spec_negative_indices_and_names() {
+        let h = headers(&["id", "Name", "city"]);
+        assert_eq!(parse_column_spec("-1", &h).unwrap(), vec![2]);
+        assert_eq!(parse_column_spec("-3", &h).unwrap(), vec![0]);
+        assert_eq!(parse_column_spec("1--1", &h).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_column_spec("name, city", &h).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn column_spec_errors() {
+        let h = headers(&["a", "b"]);
+        assert!(parse_column_spec("0", &h).is_err());
+        assert!(parse_column_spec("3", &h).is_err());
+        assert!(parse_column_spec("-3", &h).is_err());
+        assert!(parse_column_spec("x", &h).is_err());
+        assert!(parse_column_spec(" , ", &h).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+use crate::format_conversion::{self, ConversionOptions};
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+lazy_static! {
+    static ref EMAIL: Regex = Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@.]{2,}$").unwrap();
+}
+
+const DATE_FORMATS: [&str; 5] = ["%Y-%m-%d", "%d.%m.%Y", "%Y/%m/%d", "%m/%d/%Y", "%d-%m-%Y"];
+const DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%m/%d/%Y %H:%M:%S",
+];
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S%.f", "%H:%M"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnType {
+    Empty,
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    DateTime,
+    Time,
+    Email,
+    Ip,
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnStats {
+    pub index: usize,
+    pub name: String,
+    /// Gemeinsamer Typ aller nicht-leeren Werte; gemischte Spalten sind `Text`
+    pub inferred_type: ColumnType,
+    pub type_counts: BTreeMap<ColumnType, usize>,
+    pub count: usize,
+    pub null_count: usize,
+    pub distinct_count: usize,
+    /// Vergleich nach dem erkannten Typ (numerisch, chronologisch, IP-Adressen), sonst lexikografisch
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub top_values: Vec<ValueCount>,
+}
+
+/// Sortierbarer Wert je Typ, damit Min/Max nicht lexikografisch über Zahlen oder Datumsangaben laufen.
+#[derive(PartialEq, PartialOrd)]
+enum Typed {
+    Number(f64),
+    DateTime(NaiveDateTime),
+    Time(NaiveTime),
+    Ip(IpAddr),
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.naive_utc());
+    }
+    DATETIME_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS.iter().find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    TIME_FORMATS.iter().find_map(|f| NaiveTime::parse_from_str(value, f).ok())
+}
+
+fn is_null(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "" | "null" | "nil" | "none" | "n/a" | "na" | "-")
+}
+
+/// Typ eines einzelnen Wertes, zusammen mit dem Vergleichswert (falls sortierbar).
+fn classify(value: &str) -> (ColumnType, Option<Typed>) {
+    let value = value.trim();
+    if is_null(value) {
+        return (ColumnType::Empty, None);
+    }
+    if let Ok(n) = value.parse::<i64>() {
+        return (ColumnType::Integer, Some(Typed::Number(n as f64)));
+    }
+    // "inf"/"NaN" akzeptiert Rust ebenfalls, das sind hier aber Texte
+    if value.chars().any(|c| c.is_ascii_digit()) {
+        if let Ok(n) = value.parse::<f64>() {
+            return (ColumnType::Float, Some(Typed::Number(n)));
+        }
+    }
+    if matches!(value.to_lowercase().as_str(), "true" | "false" | "yes" | "no" | "ja" | "nein") {
+        return (ColumnType::Boolean, None);
+    }
+    if let Some(dt) = parse_datetime(value) {
+        return (ColumnType::DateTime, Some(Typed::DateTime(dt)));
+    }
+    if let Some(date) = parse_date(value) {
+        return (ColumnType::Date, Some(Typed::DateTime(date.and_time(NaiveTime::MIN))));
+    }
+    if let Some(time) = parse_time(value) {
+        return (ColumnType::Time, Some(Typed::Time(time)));
+    }
+    if let Ok(ip) = value.parse::<IpAddr>() {
+        return (ColumnType::Ip, Some(Typed::Ip(ip)));
+    }
+    if EMAIL.is_match(value).unwrap_or(false) {
+        return (ColumnType::Email, None);
+    }
+    (ColumnType::Text, None)
+}
+
+/// Integer und Float ergeben zusammen Float, Date und DateTime ergeben DateTime; alles andere Gemischte ist Text.
+fn infer_type(type_counts: &BTreeMap<ColumnType, usize>) -> ColumnType {
+    let mut present = type_counts.keys().copied().filter(|t| *t != ColumnType::Empty);
+    let Some(first) = present.next() else {
+        return ColumnType::Empty;
+    };
+    present.fold(first, |acc, t| match (acc, t) {
+        (a, b) if a == b => a,
+        (ColumnType::Integer, ColumnType::Float) | (ColumnType::Float, ColumnType::Integer) => ColumnType::Float,
+        (ColumnType::Date, ColumnType::DateTime) | (ColumnType::DateTime, ColumnType::Date) => ColumnType::DateTime,
+        _ => ColumnType::Text,
+    })
+}
+
+fn median(sorted: &[f64]) -> Option<f64> {
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n.is_multiple_of(2) => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+fn analyze_column(index: usize, name: &str, values: &[String], top: usize) -> ColumnStats {
+    let mut type_counts: BTreeMap<ColumnType, usize> = BTreeMap::new();
+    let mut frequencies: HashMap<&str, usize> = HashMap::new();
+    let mut typed: Vec<(Typed, &str)> = Vec::new();
+    let mut null_count = 0;
+
+    for value in values {
+        let (kind, sortable) = classify(value);
+        *type_counts.entry(kind).or_default() += 1;
+        if kind == ColumnType::Empty {
+            null_count += 1;
+            continue;
+        }
+        *frequencies.entry(value.as_str()).or_default() += 1;
+        if let Some(sortable) = sortable {
+            typed.push((sortable, value));
+        }
+    }
+
+    let inferred_type = infer_type(&type_counts);
+
+    let (min, max) = if !typed.is_empty() && inferred_type != ColumnType::Text {
+        let by_value = |a: &&(Typed, &str), b: &&(Typed, &str)| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal);
+        (
+            typed.iter().min_by(by_value).map(|(_, v)| v.to_string()),
+            typed.iter().max_by(by_value).map(|(_, v)| v.to_string()),
+        )
+    } else {
+        (
+            frequencies.keys().min().map(|v| v.to_string()),
+            frequencies.keys().max().map(|v| v.to_string()),
+        )
+    };
+
+    let (mean, median) = if matches!(inferred_type, ColumnType::Integer | ColumnType::Float) {
+        let mut numbers: Vec<f64> = typed
+            .iter()
+            .filter_map(|(t, _)| match t {
+                Typed::Number(n) => Some(*n),
+                _ => None,
+            })
+            .collect();
+        numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let mean = (!numbers.is_empty()).then(|| numbers.iter().sum::<f64>() / numbers.len() as f64);
+        (mean, median(&numbers))
+    } else {
+        (None, None)
+    };
+
+    let distinct_count = frequencies.len();
+    let mut top_values: Vec<ValueCount> = frequencies
+        .into_iter()
+        .map(|(value, count)| ValueCount { value: value.to_string(), count })
+        .collect();
+    // Bei gleicher Häufigkeit alphabetisch, damit das Ergebnis stabil bleibt
+    top_values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    top_values.truncate(top);
+
+    ColumnStats {
+        index,
+        name: name.to_string(),
+        inferred_type,
+        type_counts,
+        count: values.len(),
+        null_count,
+        distinct_count,
+        min,
+        max,
+        mean,
+        median,
+        top_values,
+    }
+}
+
+/// Splittet wie `convert_format` und liefert pro Spalte Typ und Statistik.
+pub fn analyze_columns(
+    text: &str,
+    options: &ConversionOptions,
+    top: usize,
+    op: &Operation,
+) -> Result<ProcessedText<Vec<ColumnStats>>, OperationError> {
+    let mut collector = MetricsCollector::start("analyze_columns", text.len());
+
+    let table = format_conversion::parse_table(text, options, op)?;
+    collector.phase("parse");
+
+    let total = table.columns.len();
+    let mut stats = Vec::with_capacity(total);
+    for (index, (name, values)) in table.headers.iter().zip(&table.columns).enumerate() {
+        op.progress(index, total)?;
+        stats.push(analyze_column(index, name, values, top));
+    }
+    collector.phase("process");
+
+    let metrics = collector.finish(0);
+
+    Ok(ProcessedText {
+        result_text: stats,
+        removed_lines: 0,
+        metrics,
+    })
+}
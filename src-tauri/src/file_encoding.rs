@@ -0,0 +1,263 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{EncoderResult, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+/// Mehr nicht darstellbare Zeichen werden nur noch gezählt, nicht einzeln gemeldet.
+const MAX_REPORTED_UNMAPPABLE: usize = 100;
+/// Zeichen, das beim Speichern für nicht darstellbare Zeichen geschrieben wird.
+const REPLACEMENT: u8 = b'?';
+
+/// Auswahl für das Frontend. Intern werden alle Labels von `encoding_rs` akzeptiert
+/// (Latin-1 wird dabei wie im Browser als Windows-1252 behandelt).
+pub const SUPPORTED_ENCODINGS: [&str; 12] = [
+    "UTF-8", "UTF-16LE", "UTF-16BE", "windows-1252", "ISO-8859-15", "ISO-8859-2", "windows-1250",
+    "windows-1251", "Shift_JIS", "EUC-JP", "GBK", "Big5",
+];
+
+lazy_static! {
+    /// Ursprüngliche Kodierung pro geöffneter Datei, damit beim Speichern wieder so geschrieben wird.
+    static ref OPENED_FILES: Mutex<HashMap<String, SourceEncoding>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DetectedBy {
+    Bom,
+    Utf8,
+    Utf16Heuristic,
+    Statistical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceEncoding {
+    pub encoding: &'static str,
+    pub has_bom: bool,
+    pub detected_by: DetectedBy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedFile {
+    pub path: String,
+    pub text: String,
+    pub source: SourceEncoding,
+    /// Ungültige Bytesequenzen wurden beim Dekodieren durch U+FFFD ersetzt
+    pub had_errors: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappableChar {
+    pub line: usize,
+    pub column: usize,
+    pub character: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveReport {
+    pub path: String,
+    pub encoding: &'static str,
+    pub has_bom: bool,
+    pub bytes_written: usize,
+    pub unmappable_count: usize,
+    pub unmappable: Vec<UnmappableChar>,
+}
+
+/// Liest eine Datei, erkennt die Kodierung (BOM, UTF-16 ohne BOM, gültiges UTF-8, sonst chardetng)
+/// und liefert den Text als UTF-8.
+pub fn load_file(path: &str) -> Result<LoadedFile, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Fehler beim Lesen der Datei: {:?}", e))?;
+    let source = detect_encoding(&bytes);
+    let encoding = Encoding::for_label(source.encoding.as_bytes()).unwrap_or(UTF_8);
+
+    let (text, had_errors) = if source.has_bom {
+        let (text, _, had_errors) = encoding.decode(&bytes);
+        (text.into_owned(), had_errors)
+    } else {
+        let (text, had_errors) = encoding.decode_without_bom_handling(&bytes);
+        (text.into_owned(), had_errors)
+    };
+
+    OPENED_FILES.lock().unwrap().insert(path.to_string(), source.clone());
+    Ok(LoadedFile {
+        path: path.to_string(),
+        text,
+        source,
+        had_errors,
+    })
+}
+
+pub fn detect_encoding(bytes: &[u8]) -> SourceEncoding {
+    let found = |encoding: &'static Encoding, has_bom, detected_by| SourceEncoding {
+        encoding: encoding.name(),
+        has_bom,
+        detected_by,
+    };
+
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return found(encoding, true, DetectedBy::Bom);
+    }
+    // Vor der UTF-8-Prüfung, weil UTF-16 aus reinem ASCII auch gültiges UTF-8 (mit Nullbytes) ist
+    if let Some(encoding) = guess_utf16(bytes) {
+        return found(encoding, false, DetectedBy::Utf16Heuristic);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return found(UTF_8, false, DetectedBy::Utf8);
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    found(detector.guess(None, true), false, DetectedBy::Statistical)
+}
+
+/// UTF-16 ohne BOM erkennt chardetng nicht. Text mit überwiegend ASCII-Zeichen hat dann
+/// auf jeder zweiten Position ein Nullbyte, daran lässt sich die Byte-Reihenfolge ablesen.
+fn guess_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(4096)];
+    if sample.len() < 4 || !sample.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let zero_even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let zero_odd = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+
+    if zero_odd * 10 >= pairs * 7 && zero_even * 10 <= pairs {
+        Some(UTF_16LE)
+    } else if zero_even * 10 >= pairs * 7 && zero_odd * 10 <= pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Speichert den Text in der gewählten Kodierung, ohne Angabe in der beim Öffnen erkannten.
+/// Nicht darstellbare Zeichen werden als `?` geschrieben und mit Zeile/Spalte gemeldet.
+pub fn save_file(path: &str, text: &str, encoding: Option<&str>, bom: Option<bool>) -> Result<SaveReport, String> {
+    let original = OPENED_FILES.lock().unwrap().get(path).cloned();
+
+    let target = match encoding {
+        Some(label) => Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| format!("⚠ Unbekannte Kodierung: {}", label))?,
+        None => original
+            .as_ref()
+            .and_then(|o| Encoding::for_label(o.encoding.as_bytes()))
+            .unwrap_or(UTF_8),
+    };
+    // Ein BOM bleibt nur erhalten, wenn die Kodierung gleich bleibt; UTF-16 braucht ihn zum Wiedererkennen
+    let has_bom = bom.unwrap_or_else(|| match &original {
+        Some(o) if o.encoding == target.name() => o.has_bom,
+        _ => target == UTF_16LE || target == UTF_16BE,
+    }) && is_unicode(target);
+
+    let mut unmappable = Vec::new();
+    let mut unmappable_count = 0;
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if has_bom {
+        bytes.extend_from_slice(bom_for(target));
+    }
+
+    if target == UTF_16LE {
+        text.encode_utf16().for_each(|unit| bytes.extend_from_slice(&unit.to_le_bytes()));
+    } else if target == UTF_16BE {
+        text.encode_utf16().for_each(|unit| bytes.extend_from_slice(&unit.to_be_bytes()));
+    } else {
+        let offsets = encode_into(target, text, &mut bytes);
+        unmappable_count = offsets.len();
+        unmappable = locate(text, &offsets[..offsets.len().min(MAX_REPORTED_UNMAPPABLE)]);
+    }
+
+    fs::write(path, &bytes).map_err(|e| format!("Fehler beim Speichern der Datei: {:?}", e))?;
+    OPENED_FILES.lock().unwrap().insert(
+        path.to_string(),
+        SourceEncoding {
+            encoding: target.name(),
+            has_bom,
+            detected_by: original.map(|o| o.detected_by).unwrap_or(DetectedBy::Utf8),
+        },
+    );
+
+    Ok(SaveReport {
+        path: path.to_string(),
+        encoding: target.name(),
+        has_bom,
+        bytes_written: bytes.len(),
+        unmappable_count,
+        unmappable,
+    })
+}
+
+fn is_unicode(encoding: &'static Encoding) -> bool {
+    encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE
+}
+
+fn bom_for(encoding: &'static Encoding) -> &'static [u8] {
+    if encoding == UTF_16LE {
+        &[0xFF, 0xFE]
+    } else if encoding == UTF_16BE {
+        &[0xFE, 0xFF]
+    } else {
+        &[0xEF, 0xBB, 0xBF]
+    }
+}
+
+/// Kodiert `text` und gibt die Byte-Offsets aller nicht darstellbaren Zeichen zurück.
+fn encode_into(encoding: &'static Encoding, text: &str, bytes: &mut Vec<u8>) -> Vec<usize> {
+    let mut encoder = encoding.new_encoder();
+    let mut buffer = [0u8; 8192];
+    let mut offsets = Vec::new();
+    let mut consumed = 0;
+
+    loop {
+        let (result, read, written) =
+            encoder.encode_from_utf8_without_replacement(&text[consumed..], &mut buffer, true);
+        bytes.extend_from_slice(&buffer[..written]);
+        consumed += read;
+
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(c) => {
+                offsets.push(consumed - c.len_utf8());
+                bytes.push(REPLACEMENT);
+            }
+        }
+    }
+    offsets
+}
+
+/// Rechnet Byte-Offsets in Zeile/Spalte (beide ab 1, Spalte in Zeichen) um.
+fn locate(text: &str, offsets: &[usize]) -> Vec<UnmappableChar> {
+    let mut result = Vec::with_capacity(offsets.len());
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut scanned = 0;
+
+    for &offset in offsets {
+        for (i, b) in text.as_bytes()[scanned..offset].iter().enumerate() {
+            if *b == b'\n' {
+                line += 1;
+                line_start = scanned + i + 1;
+            }
+        }
+        scanned = offset;
+
+        result.push(UnmappableChar {
+            line,
+            column: text[line_start..offset].chars().count() + 1,
+            character: text[offset..].chars().next().map(String::from).unwrap_or_default(),
+        });
+    }
+    result
+}
+
+/// Vergisst die gemerkte Kodierung, z.B. wenn das Frontend die Datei schließt.
+pub fn forget_file(path: &str) {
+    OPENED_FILES.lock().unwrap().remove(path);
+}
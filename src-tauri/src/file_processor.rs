@@ -0,0 +1,512 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::operations::{Operation, OperationError};
+use crate::metrics::MetricsCollector;
+use crate::text_processor::{LineEnding, LineRemover, ProcessedText, SortMode, TextProcessor};
+
+/// Wie viele Bytes beim externen Sortieren höchstens pro Block im Speicher gehalten werden.
+const SORT_CHUNK_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileResult {
+    pub output_path: String,
+    pub lines_read: usize,
+    pub lines_written: usize,
+}
+
+/// Liest eine Datei zeilenweise (gepuffert), meldet den Fortschritt und prüft dabei auf Abbruch.
+struct LineStream<'o> {
+    reader: BufReader<File>,
+    buffer: Vec<u8>,
+    total_bytes: u64,
+    bytes_processed: u64,
+    lines_processed: usize,
+    /// Zeilenende der ersten Zeile; die Ausgabe wird im selben Stil geschrieben
+    ending: Option<LineEnding>,
+    op: &'o Operation,
+}
+
+impl<'o> LineStream<'o> {
+    fn open(path: &str, op: &'o Operation) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Fehler beim Öffnen der Datei: {:?}", e))?;
+        let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            reader: BufReader::new(file),
+            buffer: Vec::new(),
+            total_bytes,
+            bytes_processed: 0,
+            lines_processed: 0,
+            ending: None,
+            op,
+        })
+    }
+
+    /// Nächste Zeile ohne Zeilenende. Ungültiges UTF-8 wird ersetzt statt abzubrechen.
+    /// Zeilen enden an LF, CRLF oder einem einzelnen CR (alte Mac-Dateien).
+    fn next_line(&mut self) -> Result<Option<String>, OperationError> {
+        self.buffer.clear();
+        let read_error = |e: std::io::Error| format!("Fehler beim Lesen der Datei: {:?}", e);
+        loop {
+            let available = self.reader.fill_buf().map_err(read_error)?;
+            if available.is_empty() {
+                break;
+            }
+            match available.iter().position(|&b| b == b'\n' || b == b'\r') {
+                Some(index) => {
+                    let is_cr = available[index] == b'\r';
+                    self.buffer.extend_from_slice(&available[..=index]);
+                    self.reader.consume(index + 1);
+                    // Ein CR direkt vor LF gehört zum selben Zeilenende, auch über Puffergrenzen hinweg
+                    if is_cr && self.reader.fill_buf().map_err(read_error)?.first() == Some(&b'\n') {
+                        self.buffer.push(b'\n');
+                        self.reader.consume(1);
+                    }
+                    break;
+                }
+                None => {
+                    let len = available.len();
+                    self.buffer.extend_from_slice(available);
+                    self.reader.consume(len);
+                }
+            }
+        }
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        self.bytes_processed += self.buffer.len() as u64;
+        self.lines_processed += 1;
+        self.op
+            .progress_bytes(self.lines_processed, self.bytes_processed, self.total_bytes, false)?;
+
+        let (line, ending) = if let Some(line) = self.buffer.strip_suffix(b"\r\n") {
+            (line, Some(LineEnding::Crlf))
+        } else if let Some(line) = self.buffer.strip_suffix(b"\n") {
+            (line, Some(LineEnding::Lf))
+        } else if let Some(line) = self.buffer.strip_suffix(b"\r") {
+            (line, Some(LineEnding::Cr))
+        } else {
+            (&self.buffer[..], None)
+        };
+        if self.ending.is_none() {
+            self.ending = ending;
+        }
+        Ok(Some(String::from_utf8_lossy(line).into_owned()))
+    }
+
+    fn finish(&self) -> Result<(), OperationError> {
+        self.op
+            .progress_bytes(self.lines_processed, self.bytes_processed, self.total_bytes, true)
+    }
+}
+
+/// Schreibt zunächst in eine temporäre Datei neben dem Ziel und benennt sie erst in `finish` um.
+/// So bleibt die Eingabe lesbar, auch wenn Ein- und Ausgabepfad gleich sind, und bei Abbruch
+/// oder Fehler bleibt keine halbe Ausgabedatei zurück.
+struct LineSink {
+    writer: BufWriter<File>,
+    temp_path: PathBuf,
+    target_path: PathBuf,
+    committed: bool,
+    lines_written: usize,
+    bytes_written: usize,
+    ending: &'static str,
+}
+
+impl LineSink {
+    fn create(path: &str) -> Result<Self, String> {
+        let target_path = PathBuf::from(path);
+        let file_name = target_path
+            .file_name()
+            .ok_or_else(|| format!("⚠ Ungültiger Ausgabepfad: {}", path))?
+            .to_string_lossy();
+        let temp_path = target_path.with_file_name(format!(
+            ".{}.textsplitter-{}-{}.tmp",
+            file_name,
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        let file = File::create(&temp_path).map_err(|e| format!("Fehler beim Erstellen der Ausgabedatei: {:?}", e))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            temp_path,
+            target_path,
+            committed: false,
+            lines_written: 0,
+            bytes_written: 0,
+            ending: "\n",
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        write!(self.writer, "{}{}", line, self.ending).map_err(|e| format!("Fehler beim Schreiben: {:?}", e))?;
+        self.lines_written += 1;
+        self.bytes_written += line.len() + self.ending.len();
+        Ok(())
+    }
+
+    fn keep_ending(&mut self, ending: Option<LineEnding>) {
+        if let Some(ending) = ending {
+            self.ending = ending.as_str();
+        }
+    }
+
+    /// Ersetzt die Zieldatei durch die geschriebene Ausgabe und gibt die Anzahl geschriebener Zeilen und Bytes zurück.
+    fn finish(mut self) -> Result<(usize, usize), String> {
+        self.writer.flush().map_err(|e| format!("Fehler beim Schreiben: {:?}", e))?;
+        fs::rename(&self.temp_path, &self.target_path)
+            .map_err(|e| format!("Fehler beim Ersetzen der Ausgabedatei: {:?}", e))?;
+        self.committed = true;
+        Ok((self.lines_written, self.bytes_written))
+    }
+}
+
+impl Drop for LineSink {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Gemeinsamer Rahmen für alle Datei-Operationen: Zeilen lesen, `handle` anwenden, Ergebnis schreiben.
+fn process_lines<F>(
+    command: &'static str,
+    input_path: &str,
+    output_path: &str,
+    op: &Operation,
+    mut handle: F,
+) -> Result<ProcessedText<FileResult>, OperationError>
+where
+    F: FnMut(&str, &mut LineSink) -> Result<(), String>,
+{
+    let mut stream = LineStream::open(input_path, op)?;
+    let mut collector = MetricsCollector::start(command, stream.total_bytes as usize);
+    let mut sink = LineSink::create(output_path)?;
+
+    while let Some(line) = stream.next_line()? {
+        sink.keep_ending(stream.ending);
+        handle(&line, &mut sink)?;
+    }
+    stream.finish()?;
+
+    let lines_read = stream.lines_processed;
+    // Die Eingabe schließen, bevor die Ausgabe sie ggf. ersetzt
+    drop(stream);
+    let (lines_written, bytes_written) = sink.finish()?;
+    collector.phase("process");
+    let metrics = collector.finish(bytes_written);
+
+    Ok(ProcessedText {
+        result_text: FileResult {
+            output_path: output_path.to_string(),
+            lines_read,
+            lines_written,
+        },
+        removed_lines: lines_read.saturating_sub(lines_written),
+        metrics,
+    })
+}
+
+/// Teilt jede Zeile am Delimiter und schreibt die Spalten mit `output_separator` verbunden zurück.
+pub fn split_file(
+    input_path: &str,
+    output_path: &str,
+    delimiter: &str,
+    output_separator: &str,
+    trimparts: bool,
+    useregex: bool,
+    op: &Operation,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let regex = TextProcessor::build_split_regex(delimiter, useregex)?;
+
+    process_lines("split_file", input_path, output_path, op, |line, sink| {
+        let parts: Vec<&str> = TextProcessor::split_line(line, delimiter, regex.as_ref())
+            .into_iter()
+            .map(|part| if trimparts { part.trim() } else { part })
+            .collect();
+        sink.write_line(&parts.join(output_separator))
+    })
+}
+
+pub fn filter_file(
+    input_path: &str,
+    output_path: &str,
+    pattern: &str,
+    filter_mode: &str,
+    casesensitive: bool,
+    splitmatches: bool,
+    op: &Operation,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let regex = TextProcessor::build_filter_regex(pattern, casesensitive)?;
+
+    process_lines("filter_file", input_path, output_path, op, |line, sink| {
+        let filtered = TextProcessor::filter_line(line, &regex, filter_mode, splitmatches);
+        if filtered.is_empty() {
+            Ok(())
+        } else {
+            sink.write_line(&filtered)
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn remove_lines_file(
+    input_path: &str,
+    output_path: &str,
+    pattern: &str,
+    removemode: &str,
+    casesensitive: bool,
+    useregex: bool,
+    trimparts: bool,
+    op: &Operation,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let mut remover = LineRemover::new(pattern, removemode, casesensitive, useregex, trimparts)?;
+
+    process_lines("remove_lines_file", input_path, output_path, op, |line, sink| match remover.apply(line) {
+        Some(kept) => sink.write_line(kept),
+        None => Ok(()),
+    })
+}
+
+/// Externes Merge-Sort: sortierte Blöcke von höchstens `SORT_CHUNK_BYTES` werden in
+/// temporäre Dateien geschrieben und anschließend zusammengeführt.
+pub fn sort_file(
+    input_path: &str,
+    output_path: &str,
+    sortmode: &str,
+    casesensitive: bool,
+    op: &Operation,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    sort_file_in_chunks(input_path, output_path, sortmode, casesensitive, SORT_CHUNK_BYTES, op)
+}
+
+fn sort_file_in_chunks(
+    input_path: &str,
+    output_path: &str,
+    sortmode: &str,
+    casesensitive: bool,
+    max_chunk_bytes: usize,
+    op: &Operation,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let mode = SortMode::parse(sortmode)?;
+    let compare = |a: &String, b: &String| mode.compare(a, b, casesensitive);
+
+    let mut stream = LineStream::open(input_path, op)?;
+    let mut collector = MetricsCollector::start("sort_file", stream.total_bytes as usize);
+    let mut chunk: Vec<String> = Vec::new();
+    let mut chunk_bytes = 0;
+    let mut chunk_files: Vec<PathBuf> = Vec::new();
+    let sort_id = TEMP_COUNTER.fetch_add(1, AtomicOrdering::Relaxed);
+
+    let result = (|| -> Result<(usize, usize), OperationError> {
+        while let Some(line) = stream.next_line()? {
+            chunk_bytes += line.len();
+            chunk.push(line);
+            if chunk_bytes >= max_chunk_bytes {
+                chunk.sort_by(compare);
+                write_chunk(&chunk, sort_id, &mut chunk_files)?;
+                chunk.clear();
+                chunk_bytes = 0;
+            }
+        }
+        stream.finish()?;
+        chunk.sort_by(compare);
+        collector.phase("process");
+
+        let mut sink = LineSink::create(output_path)?;
+        sink.keep_ending(stream.ending);
+        if chunk_files.is_empty() {
+            for line in &chunk {
+                sink.write_line(line)?;
+            }
+        } else {
+            if !chunk.is_empty() {
+                write_chunk(&chunk, sort_id, &mut chunk_files)?;
+                chunk.clear();
+            }
+            merge_chunks(&chunk_files, &mut sink, op, |a, b| compare(a, b))?;
+        }
+        let written = sink.finish()?;
+        collector.phase("merge");
+        Ok(written)
+    })();
+
+    for path in &chunk_files {
+        let _ = fs::remove_file(path);
+    }
+    let (lines_written, bytes_written) = result?;
+    let lines_read = stream.lines_processed;
+    let metrics = collector.finish(bytes_written);
+
+    Ok(ProcessedText {
+        result_text: FileResult {
+            output_path: output_path.to_string(),
+            lines_read,
+            lines_written,
+        },
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+/// Zählt Sortierläufe und Ausgabedateien, damit parallele Aufrufe im selben Prozess eigene temporäre Dateien haben.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Der Pfad wird vor dem Schreiben eingetragen, damit auch halb geschriebene Blöcke aufgeräumt werden.
+fn write_chunk(lines: &[String], sort_id: usize, chunk_files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!(
+        "textsplitter-sort-{}-{}-{}.tmp",
+        std::process::id(),
+        sort_id,
+        chunk_files.len()
+    ));
+    chunk_files.push(path.clone());
+    let mut sink = LineSink::create(path.to_str().unwrap_or_default())?;
+    for line in lines {
+        sink.write_line(line)?;
+    }
+    sink.finish()?;
+    Ok(())
+}
+
+/// K-Wege-Merge der sortierten Blöcke. Die Anzahl der Blöcke ist klein, daher reicht eine lineare Suche.
+fn merge_chunks<C>(paths: &[PathBuf], sink: &mut LineSink, op: &Operation, compare: C) -> Result<(), OperationError>
+where
+    C: Fn(&String, &String) -> Ordering,
+{
+    let mut readers = paths
+        .iter()
+        .map(|p| open_chunk(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut heads: Vec<Option<String>> = readers.iter_mut().map(read_chunk_line).collect::<Result<_, _>>()?;
+
+    loop {
+        let next = heads
+            .iter()
+            .enumerate()
+            .filter_map(|(i, head)| head.as_ref().map(|line| (i, line)))
+            .min_by(|(_, a), (_, b)| compare(a, b))
+            .map(|(i, _)| i);
+
+        let Some(index) = next else { break };
+        op.check()?;
+        if let Some(line) = heads[index].take() {
+            sink.write_line(&line)?;
+        }
+        heads[index] = read_chunk_line(&mut readers[index])?;
+    }
+    Ok(())
+}
+
+fn open_chunk(path: &Path) -> Result<BufReader<File>, String> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| format!("Fehler beim Öffnen der temporären Datei: {:?}", e))
+}
+
+fn read_chunk_line(reader: &mut BufReader<File>) -> Result<Option<String>, String> {
+    let mut line = String::new();
+    let read = reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Fehler beim Lesen der temporären Datei: {:?}", e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    Ok(Some(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("textsplitter-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn output_may_replace_input() {
+        let path = temp_file("same-path", "a;b\nc;d\n");
+        let path_str = path.to_str().unwrap();
+        split_file(path_str, path_str, ";", "|", false, false, &Operation::untracked()).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a|b\nc|d\n");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn sort_merges_several_chunks() {
+        let lines: Vec<String> = (0..500).map(|i| format!("line {:03}", (i * 7919) % 500)).collect();
+        let input = temp_file("sort-chunks", &(lines.join("\r\n") + "\r\n"));
+        let output = input.with_file_name("sorted.txt");
+        let result = sort_file_in_chunks(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            "alphabetical",
+            true,
+            100,
+            &Operation::untracked(),
+        )
+        .unwrap();
+
+        let mut expected = lines.clone();
+        expected.sort();
+        assert_eq!(result.result_text.lines_written, 500);
+        assert_eq!(fs::read_to_string(&output).unwrap(), expected.join("\r\n") + "\r\n");
+        let chunk_prefix = format!("textsplitter-sort-{}-", std::process::id());
+        let leftover = fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_name().to_string_lossy().starts_with(&chunk_prefix));
+        assert!(!leftover);
+        fs::remove_dir_all(input.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn line_endings_are_kept() {
+        for content in ["a;b\rc;d\r", "a;b\r\nc;d", "a;b\nc;d\n"] {
+            let input = temp_file("endings", content);
+            let output = input.with_file_name("output.txt");
+            let op = Operation::untracked();
+            let result =
+                split_file(input.to_str().unwrap(), output.to_str().unwrap(), ";", "|", false, false, &op).unwrap();
+
+            assert_eq!(result.result_text.lines_read, 2, "{:?}", content);
+            let ending = TextProcessor::detect_line_endings(content).dominant.unwrap().as_str();
+            assert_eq!(fs::read_to_string(&output).unwrap(), format!("a|b{0}c|d{0}", ending));
+            fs::remove_dir_all(input.parent().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn failed_run_leaves_no_output() {
+        let input = temp_file("failed-run", "keep\nbad\n");
+        let output = input.with_file_name("output.txt");
+        let op = Operation::untracked();
+        let result = process_lines("test", input.to_str().unwrap(), output.to_str().unwrap(), &op, |line, sink| {
+            if line == "bad" {
+                Err("kaputt".to_string())
+            } else {
+                sink.write_line(line)
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(input.parent().unwrap()).unwrap().count(), 1);
+        fs::remove_dir_all(input.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,456 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::json_tools;
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{ProcessedText, TextProcessor};
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceFormat {
+    #[default]
+    Delimited,
+    /// Array von Objekten, einzelnes Objekt oder JSON Lines
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TargetFormat {
+    #[default]
+    Json,
+    JsonLines,
+    Markdown,
+    Ascii,
+    Html,
+    Sql,
+    Csv,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConversionOptions {
+    pub from: SourceFormat,
+    pub to: TargetFormat,
+    /// Eingabe-Delimiter (wie beim Split), nur für `Delimited`
+    pub delimiter: String,
+    pub useregex: bool,
+    pub trimparts: bool,
+    /// Felder in doppelten Anführungszeichen dürfen den Delimiter enthalten (CSV nach RFC 4180, einzeilig)
+    pub quoted: bool,
+    /// Erste Zeile enthält die Spaltennamen
+    pub header: bool,
+    pub output_delimiter: String,
+    pub table_name: String,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            from: SourceFormat::Delimited,
+            to: TargetFormat::Json,
+            delimiter: ",".to_string(),
+            useregex: false,
+            trimparts: false,
+            quoted: true,
+            header: true,
+            output_delimiter: ",".to_string(),
+            table_name: "data".to_string(),
+        }
+    }
+}
+
+/// Spaltenmodell wie bei `process_text_block_to_column_strings`, ergänzt um die Spaltennamen.
+pub struct Table {
+    pub headers: Vec<String>,
+    pub columns: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn row_count(&self) -> usize {
+        self.columns.first().map_or(0, Vec::len)
+    }
+
+    pub(crate) fn row(&self, index: usize) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(move |col| col[index].as_str())
+    }
+
+    /// Spaltenbreiten in Zeichen, inklusive Überschrift.
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .zip(&self.columns)
+            .map(|(header, col)| col.iter().chain(Some(header)).map(|c| c.chars().count()).max().unwrap_or(0))
+            .collect()
+    }
+
+    /// Leere Spaltennamen werden zu `columnN`, doppelte bekommen ein `_2`, `_3`, ... angehängt,
+    /// damit sie als JSON-Keys eindeutig bleiben.
+    fn from_columns(mut columns: Vec<Vec<String>>, header: bool) -> Self {
+        let mut headers: Vec<String> = Vec::with_capacity(columns.len());
+        for (i, col) in columns.iter_mut().enumerate() {
+            let name = if header && !col.is_empty() { col.remove(0) } else { String::new() };
+            let base = if name.trim().is_empty() { format!("column{}", i + 1) } else { name };
+            let mut unique = base.clone();
+            let mut n = 2;
+            while headers.contains(&unique) {
+                unique = format!("{}_{}", base, n);
+                n += 1;
+            }
+            headers.push(unique);
+        }
+        Self { headers, columns }
+    }
+}
+
+/// Liest Text als Tabelle: entweder mit dem Split-Delimiter oder aus JSON (Spalten = Vereinigung aller Keys).
+pub fn parse_table(text: &str, options: &ConversionOptions, op: &Operation) -> Result<Table, OperationError> {
+    match options.from {
+        SourceFormat::Delimited => {
+            let use_quotes = options.quoted && !options.useregex;
+            let regex = TextProcessor::build_split_regex(&options.delimiter, options.useregex)?;
+            let columns = TextProcessor::split_columns(text, op, |line| {
+                let parts = if use_quotes {
+                    split_quoted(line, &options.delimiter)
+                } else {
+                    TextProcessor::split_line(line, &options.delimiter, regex.as_ref())
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                };
+                parts
+                    .into_iter()
+                    .map(|part| if options.trimparts { part.trim().to_string() } else { part })
+                    .collect()
+            })?;
+            Ok(Table::from_columns(columns, options.header))
+        }
+        SourceFormat::Json => table_from_json(text, op),
+    }
+}
+
+/// Trennt am Delimiter, außer innerhalb von `"..."`; `""` steht für ein einzelnes Anführungszeichen.
+fn split_quoted(line: &str, delimiter: &str) -> Vec<String> {
+    if delimiter.is_empty() {
+        return vec![line.to_string()];
+    }
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        if in_quotes {
+            if let Some(after) = rest.strip_prefix("\"\"") {
+                current.push('"');
+                rest = after;
+                continue;
+            }
+            if c == '"' {
+                in_quotes = false;
+            } else {
+                current.push(c);
+            }
+        } else if c == '"' && current.trim().is_empty() {
+            current.clear();
+            in_quotes = true;
+        } else if let Some(after) = rest.strip_prefix(delimiter) {
+            parts.push(std::mem::take(&mut current));
+            rest = after;
+            continue;
+        } else {
+            current.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    parts.push(current);
+    parts
+}
+
+fn table_from_json(text: &str, op: &Operation) -> Result<Table, OperationError> {
+    let records: Vec<Value> = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => items,
+        Ok(value) => vec![value],
+        // Kein einzelnes JSON-Dokument: nur als JSON Lines lesen, wenn es danach aussieht
+        Err(_) if json_tools::is_json_lines(text, None) => text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| json_error(&e, i + 1))
+            })
+            .collect::<Result<_, _>>()?,
+        Err(e) => return Err(json_error(&e, e.line()).into()),
+    };
+
+    let mut headers: Vec<String> = Vec::new();
+    for record in &records {
+        match record {
+            Value::Object(map) => {
+                for key in map.keys() {
+                    if !headers.contains(key) {
+                        headers.push(key.clone());
+                    }
+                }
+            }
+            _ => {
+                if !headers.iter().any(|h| h == "value") {
+                    headers.push("value".to_string());
+                }
+            }
+        }
+    }
+
+    let mut columns: Vec<Vec<String>> = vec![Vec::with_capacity(records.len()); headers.len()];
+    for (index, record) in records.iter().enumerate() {
+        op.progress(index, records.len())?;
+        for (header, column) in headers.iter().zip(columns.iter_mut()) {
+            let cell = match record {
+                Value::Object(map) => map.get(header),
+                other if header == "value" => Some(other),
+                _ => None,
+            };
+            column.push(cell_text(cell));
+        }
+    }
+
+    Ok(Table { headers, columns })
+}
+
+/// serde_json hängt die Position an die Meldung an, Zeile/Spalte stehen schon separat drin.
+fn json_error(error: &serde_json::Error, line: usize) -> String {
+    let message = error.to_string();
+    let message = message.split(" at line ").next().unwrap_or(&message);
+    format!("⚠ Ungültiges JSON in Zeile {}, Spalte {}: {}", line, error.column(), message)
+}
+
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+pub fn convert_format(
+    text: &str,
+    options: &ConversionOptions,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("convert_format", text.len());
+
+    let table = parse_table(text, options, op)?;
+    collector.phase("parse");
+
+    op.check()?;
+    let result_text = match options.to {
+        TargetFormat::Json => to_json(&table, false)?,
+        TargetFormat::JsonLines => to_json(&table, true)?,
+        TargetFormat::Markdown => to_markdown(&table),
+        TargetFormat::Ascii => to_ascii(&table),
+        TargetFormat::Html => to_html(&table),
+        TargetFormat::Sql => to_sql(&table, &options.table_name),
+        TargetFormat::Csv => to_csv(&table, &options.output_delimiter),
+    };
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+fn row_object(table: &Table, index: usize) -> Value {
+    let map: Map<String, Value> = table
+        .headers
+        .iter()
+        .cloned()
+        .zip(table.row(index).map(|cell| Value::String(cell.to_string())))
+        .collect();
+    Value::Object(map)
+}
+
+fn to_json(table: &Table, lines: bool) -> Result<String, String> {
+    let rows = (0..table.row_count()).map(|i| row_object(table, i));
+    if lines {
+        rows.map(|row| serde_json::to_string(&row).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|rows| rows.join("\n"))
+    } else {
+        serde_json::to_string_pretty(&Value::Array(rows.collect())).map_err(|e| e.to_string())
+    }
+}
+
+fn pad(cell: &str, width: usize) -> String {
+    format!("{}{}", cell, " ".repeat(width.saturating_sub(cell.chars().count())))
+}
+
+/// Zeilenumbrüche und `|` in Zellen würden die Zeilen bzw. Spalten von Markdown- und ASCII-Tabellen zerreißen.
+fn escape_cells(table: &Table, line_break: &str) -> Table {
+    let escape = |cell: &str| {
+        cell.replace("\r\n", "\n")
+            .replace(['\r', '\n'], line_break)
+            .replace('|', "\\|")
+    };
+    Table {
+        headers: table.headers.iter().map(|h| escape(h)).collect(),
+        columns: table.columns.iter().map(|col| col.iter().map(|c| escape(c)).collect()).collect(),
+    }
+}
+
+fn to_markdown(table: &Table) -> String {
+    let escaped = escape_cells(table, "<br>");
+    let widths: Vec<usize> = escaped.widths().into_iter().map(|w| w.max(3)).collect();
+
+    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut out = vec![
+        line(escaped.headers.iter().zip(&widths).map(|(h, w)| pad(h, *w)).collect()),
+        line(widths.iter().map(|w| "-".repeat(*w)).collect()),
+    ];
+    for i in 0..escaped.row_count() {
+        out.push(line(escaped.row(i).zip(&widths).map(|(c, w)| pad(c, *w)).collect()));
+    }
+    out.join("\n")
+}
+
+fn to_ascii(table: &Table) -> String {
+    let table = &escape_cells(table, " ");
+    let widths = table.widths();
+    let border = format!("+{}+", widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>().join("+"));
+    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+    let mut out = vec![
+        border.clone(),
+        line(table.headers.iter().zip(&widths).map(|(h, w)| pad(h, *w)).collect()),
+        border.clone(),
+    ];
+    for i in 0..table.row_count() {
+        out.push(line(table.row(i).zip(&widths).map(|(c, w)| pad(c, *w)).collect()));
+    }
+    out.push(border);
+    out.join("\n")
+}
+
+fn escape_html(cell: &str) -> String {
+    cell.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_html(table: &Table) -> String {
+    let cells = |tag: &str, values: Vec<&str>| {
+        values
+            .into_iter()
+            .map(|v| format!("<{tag}>{}</{tag}>", escape_html(v)))
+            .collect::<String>()
+    };
+
+    let mut out = vec![
+        "<table>".to_string(),
+        "  <thead>".to_string(),
+        format!("    <tr>{}</tr>", cells("th", table.headers.iter().map(String::as_str).collect())),
+        "  </thead>".to_string(),
+        "  <tbody>".to_string(),
+    ];
+    for i in 0..table.row_count() {
+        out.push(format!("    <tr>{}</tr>", cells("td", table.row(i).collect())));
+    }
+    out.push("  </tbody>".to_string());
+    out.push("</table>".to_string());
+    out.join("\n")
+}
+
+/// Bezeichner in doppelten, Werte in einfachen Anführungszeichen (jeweils verdoppelt escaped).
+/// Leere Zellen werden zu `NULL`.
+fn to_sql(table: &Table, table_name: &str) -> String {
+    let identifier = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    let columns = table.headers.iter().map(|h| identifier(h)).collect::<Vec<_>>().join(", ");
+    let name = if table_name.trim().is_empty() { "data" } else { table_name.trim() };
+
+    (0..table.row_count())
+        .map(|i| {
+            let values = table
+                .row(i)
+                .map(|cell| {
+                    if cell.is_empty() {
+                        "NULL".to_string()
+                    } else {
+                        format!("'{}'", cell.replace('\'', "''"))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("INSERT INTO {} ({}) VALUES ({});", identifier(name), columns, values)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Verbindet Zellen mit dem Delimiter; mit `quote` werden Zellen, die Delimiter, `"` oder Zeilenumbrüche
+/// enthalten, wie in CSV in Anführungszeichen gesetzt.
+pub(crate) fn join_delimited<'a>(cells: impl IntoIterator<Item = &'a str>, delimiter: &str, quote: bool) -> String {
+    cells
+        .into_iter()
+        .map(|cell| {
+            if quote && ((!delimiter.is_empty() && cell.contains(delimiter)) || cell.contains(['"', '\n', '\r'])) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(delimiter)
+}
+
+fn to_csv(table: &Table, delimiter: &str) -> String {
+    let delimiter = if delimiter.is_empty() { "," } else { delimiter };
+    let mut out = vec![join_delimited(table.headers.iter().map(String::as_str), delimiter, true)];
+    for i in 0..table.row_count() {
+        out.push(join_delimited(table.row(i), delimiter, true));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(text: &str, from: SourceFormat, to: TargetFormat) -> Result<String, OperationError> {
+        let options = ConversionOptions {
+            from,
+            to,
+            ..Default::default()
+        };
+        convert_format(text, &options, &Operation::untracked()).map(|result| result.result_text)
+    }
+
+    #[test]
+    fn json_document_error_keeps_its_position() {
+        let text = "[\n  {\"a\": 1},\n  {\"a\": 2,}\n]";
+        let Err(error) = convert(text, SourceFormat::Json, TargetFormat::Csv) else {
+            panic!("Fehler erwartet");
+        };
+        assert!(error.to_string().starts_with("⚠ Ungültiges JSON in Zeile 3, Spalte"), "{}", error);
+    }
+
+    #[test]
+    fn json_lines_are_still_detected() {
+        let csv = convert("{\"a\": 1}\n{\"a\": 2}", SourceFormat::Json, TargetFormat::Csv).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+    }
+
+    #[test]
+    fn table_cells_with_line_breaks_and_pipes() {
+        let json = r#"[{"name": "a|b", "note": "one\ntwo"}]"#;
+        let markdown = convert(json, SourceFormat::Json, TargetFormat::Markdown).unwrap();
+        assert_eq!(markdown.lines().nth(2).unwrap(), "| a\\|b | one<br>two |");
+
+        let ascii = convert(json, SourceFormat::Json, TargetFormat::Ascii).unwrap();
+        assert_eq!(ascii.lines().count(), 5);
+        assert!(ascii.contains("| a\\|b | one two |"), "{}", ascii);
+    }
+}
@@ -0,0 +1,193 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use md5::Md5;
+use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::column_projection;
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{ProcessedText, TextProcessor};
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+    Crc32,
+    /// XXH64 mit Seed 0
+    Xxh64,
+    /// XXH3 (64 Bit) mit Seed 0
+    Xxh3,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestFormat {
+    #[default]
+    Hex,
+    Base64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HashOptions {
+    pub algorithm: HashAlgorithm,
+    pub format: DigestFormat,
+    /// Jede Zeile einzeln hashen und den Hash hinter den Originalwert schreiben
+    pub perline: bool,
+    /// Nur diese Spalte hashen (Nummer, negativer Index oder Spaltenname wie bei `project_columns`)
+    pub column: Option<String>,
+    pub delimiter: String,
+    pub useregex: bool,
+    pub trimparts: bool,
+    /// Erste Zeile enthält Spaltennamen; sie bekommt statt eines Hashes den Algorithmusnamen
+    pub header: bool,
+    /// Trenner zwischen Originalwert und Hash
+    pub separator: String,
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: HashAlgorithm::Sha256,
+            format: DigestFormat::Hex,
+            perline: false,
+            column: None,
+            delimiter: "\t".to_string(),
+            useregex: false,
+            trimparts: false,
+            header: false,
+            separator: "\t".to_string(),
+        }
+    }
+}
+
+impl HashAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Crc32 => "crc32",
+            HashAlgorithm::Xxh64 => "xxh64",
+            HashAlgorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    /// Prüfsummen werden big-endian ausgegeben, damit Hex dem üblichen Format (z.B. `crc32`-Tool) entspricht.
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Md5 => Md5::digest(data).to_vec(),
+            HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+            HashAlgorithm::Blake3 => blake3::hash(data).as_bytes().to_vec(),
+            HashAlgorithm::Crc32 => crc32fast::hash(data).to_be_bytes().to_vec(),
+            HashAlgorithm::Xxh64 => xxhash_rust::xxh64::xxh64(data, 0).to_be_bytes().to_vec(),
+            HashAlgorithm::Xxh3 => xxhash_rust::xxh3::xxh3_64(data).to_be_bytes().to_vec(),
+        }
+    }
+}
+
+fn format_digest(digest: &[u8], format: DigestFormat) -> String {
+    match format {
+        DigestFormat::Hex => digest.iter().map(|b| format!("{:02x}", b)).collect(),
+        DigestFormat::Base64 => STANDARD.encode(digest),
+    }
+}
+
+pub fn hash_value(value: &str, algorithm: HashAlgorithm, format: DigestFormat) -> String {
+    format_digest(&algorithm.digest(value.as_bytes()), format)
+}
+
+/// Hasht den ganzen Text (Ergebnis ist nur der Hash) oder jede Zeile bzw. eine Spalte jeder Zeile.
+/// Leere Zeilen bleiben leer, Zeilen ohne die gewählte Spalte bekommen einen leeren Hash.
+pub fn hash_text(text: &str, options: &HashOptions, op: &Operation) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("hash_text", text.len());
+
+    if !options.perline {
+        collector.phase("parse");
+        let result_text = hash_value(text, options.algorithm, options.format);
+        collector.phase("process");
+        let metrics = collector.finish(result_text.len());
+        return Ok(ProcessedText {
+            result_text,
+            removed_lines: 0,
+            metrics,
+        });
+    }
+
+    let regex = TextProcessor::build_split_regex(&options.delimiter, options.useregex)?;
+    let column = options.column.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    let split = |line: &str| -> Vec<String> {
+        TextProcessor::split_line(line, &options.delimiter, regex.as_ref())
+            .into_iter()
+            .map(|part| if options.trimparts { part.trim().to_string() } else { part.to_string() })
+            .collect()
+    };
+    let headers: Option<Vec<String>> = (options.header && column.is_some())
+        .then(|| lines.first().map(|line| split(line)))
+        .flatten();
+    let column_names = |parts: &[String]| match &headers {
+        Some(headers) => headers.clone(),
+        None => (1..=parts.len()).map(|i| format!("column{}", i)).collect(),
+    };
+
+    // Spalte einmal anhand der ersten Datenzeile auflösen (negative Indizes zählen von deren Ende);
+    // Tippfehler werden so sofort gemeldet statt jede Zeile mit leerem Hash auszugeben
+    let mut column_index = None;
+    if let Some(spec) = column {
+        let first = lines.iter().skip(usize::from(options.header)).find(|line| !line.is_empty());
+        if let Some(first) = first {
+            let selected = column_projection::parse_column_spec(spec, &column_names(&split(first)))?;
+            if selected.len() != 1 {
+                return Err("⚠ Zum Hashen genau eine Spalte angeben".to_string().into());
+            }
+            column_index = Some(selected[0]);
+        }
+    }
+    collector.phase("parse");
+
+    let mut hashed: Vec<String> = Vec::with_capacity(total_lines);
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+
+        if options.header && index == 0 {
+            hashed.push(format!("{}{}{}", line, options.separator, options.algorithm.name()));
+            continue;
+        }
+        if line.is_empty() {
+            hashed.push(String::new());
+            continue;
+        }
+
+        let value = match column_index {
+            Some(index) => split(line).into_iter().nth(index),
+            None => Some(line.to_string()),
+        };
+
+        let digest = value.map(|v| hash_value(&v, options.algorithm, options.format)).unwrap_or_default();
+        hashed.push(format!("{}{}{}", line, options.separator, digest));
+    }
+    collector.phase("process");
+
+    let result_text = hashed.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: 0,
+        metrics,
+    })
+}
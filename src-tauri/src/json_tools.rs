@@ -0,0 +1,519 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+/// Ein Datensatz: bei JSON Lines eine Zeile, sonst der ganze Text. `line` ist die erste Zeile (ab 1).
+struct Record<'a> {
+    line: usize,
+    text: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonValidation {
+    pub valid: bool,
+    pub json_lines: bool,
+    pub records: usize,
+    pub errors: Vec<JsonError>,
+}
+
+/// `None` erkennt JSON Lines automatisch: mehrere nicht-leere Zeilen, die einzeln gültiges JSON sind,
+/// der Gesamttext aber nicht.
+pub(crate) fn is_json_lines(text: &str, jsonlines: Option<bool>) -> bool {
+    jsonlines.unwrap_or_else(|| {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let first = lines.next();
+        let has_more = lines.next().is_some();
+        has_more
+            && serde_json::from_str::<Value>(text).is_err()
+            && first.is_some_and(|l| serde_json::from_str::<Value>(l).is_ok())
+    })
+}
+
+fn split_records(text: &str, json_lines: bool) -> Vec<Record<'_>> {
+    if !json_lines {
+        return vec![Record { line: 1, text }];
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| Record { line: i + 1, text: l })
+        .collect()
+}
+
+fn parse_record(record: &Record) -> Result<Value, JsonError> {
+    serde_json::from_str(record.text).map_err(|e| {
+        // serde_json hängt die Position relativ zum Datensatz an, Zeile/Spalte stehen schon separat drin
+        let message = e.to_string();
+        JsonError {
+            line: record.line + e.line().saturating_sub(1),
+            column: e.column(),
+            message: message.split(" at line ").next().unwrap_or(&message).to_string(),
+        }
+    })
+}
+
+fn error_message(error: &JsonError) -> String {
+    format!("⚠ Ungültiges JSON in Zeile {}, Spalte {}: {}", error.line, error.column, error.message)
+}
+
+/// Gemeinsamer Ablauf für alle Befehle, die pro Datensatz einen Text erzeugen.
+fn transform_records<F>(
+    command: &'static str,
+    text: &str,
+    jsonlines: Option<bool>,
+    op: &Operation,
+    mut transform: F,
+) -> Result<ProcessedText<String>, OperationError>
+where
+    F: FnMut(Value) -> Result<Vec<String>, String>,
+{
+    let mut collector = MetricsCollector::start(command, text.len());
+
+    let json_lines = is_json_lines(text, jsonlines);
+    let records = split_records(text, json_lines);
+    collector.phase("parse");
+
+    let mut output: Vec<String> = Vec::with_capacity(records.len());
+    for (index, record) in records.iter().enumerate() {
+        op.progress(index, records.len())?;
+        let value = parse_record(record).map_err(|e| error_message(&e))?;
+        output.extend(transform(value)?);
+    }
+    collector.phase("process");
+
+    let result_text = output.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+fn to_pretty(value: &Value, indent: &[u8]) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, PrettyFormatter::with_indent(indent));
+    value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+    String::from_utf8(buffer).map_err(|e| e.to_string())
+}
+
+/// Formatiert mit `indent` Leerzeichen (0 = Tab). Bei JSON Lines bleibt jeder Datensatz einzeilig,
+/// sonst wäre die Ausgabe kein gültiges JSONL mehr.
+pub fn pretty_print(
+    text: &str,
+    indent: usize,
+    sortkeys: bool,
+    jsonlines: Option<bool>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let json_lines = is_json_lines(text, jsonlines);
+    let indent = if indent == 0 { "\t".to_string() } else { " ".repeat(indent) };
+
+    transform_records("pretty_print_json", text, Some(json_lines), op, |value| {
+        let value = if sortkeys { sort_keys(value) } else { value };
+        if json_lines {
+            serde_json::to_string(&value).map(|s| vec![s]).map_err(|e| e.to_string())
+        } else {
+            to_pretty(&value, indent.as_bytes()).map(|s| vec![s])
+        }
+    })
+}
+
+pub fn minify(
+    text: &str,
+    sortkeys: bool,
+    jsonlines: Option<bool>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    transform_records("minify_json", text, jsonlines, op, |value| {
+        let value = if sortkeys { sort_keys(value) } else { value };
+        serde_json::to_string(&value).map(|s| vec![s]).map_err(|e| e.to_string())
+    })
+}
+
+/// Prüft den Text (bzw. jede Zeile bei JSON Lines) und sammelt alle Fehler mit Zeile/Spalte.
+pub fn validate(text: &str, jsonlines: Option<bool>, op: &Operation) -> Result<ProcessedText<JsonValidation>, OperationError> {
+    let mut collector = MetricsCollector::start("validate_json", text.len());
+
+    let json_lines = is_json_lines(text, jsonlines);
+    let records = split_records(text, json_lines);
+    collector.phase("parse");
+
+    let mut errors = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        op.progress(index, records.len())?;
+        if let Err(error) = parse_record(record) {
+            errors.push(error);
+        }
+    }
+    collector.phase("process");
+    let metrics = collector.finish(0);
+
+    Ok(ProcessedText {
+        result_text: JsonValidation {
+            valid: errors.is_empty(),
+            json_lines,
+            records: records.len(),
+            errors,
+        },
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+/// Wertet einen JSONPath-Ausdruck aus und gibt jeden Treffer als eigene Zeile aus.
+/// Mit `raw` werden Strings ohne Anführungszeichen ausgegeben.
+pub fn extract(
+    text: &str,
+    path: &str,
+    raw: bool,
+    jsonlines: Option<bool>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let path = JsonPath::parse(path)?;
+
+    transform_records("extract_json", text, jsonlines, op, |value| {
+        path.select(&value)
+            .into_iter()
+            .map(|found| match found {
+                Value::String(s) if raw => Ok(s.clone()),
+                other => serde_json::to_string(other).map_err(|e| e.to_string()),
+            })
+            .collect()
+    })
+}
+
+/// Teilmenge von JSONPath: `$`, `.key`, `['key']`, `[0]`, `[-1]`, `[0,2]`, `[1:3]`, `*`, `..key`
+/// und Filter wie `[?(@.status == 'active')]` oder `[?(@.id)]`.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Union(Vec<Segment>),
+    Wildcard,
+    /// `..key` bzw. `..*`
+    Descendant(Option<String>),
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone)]
+struct Filter {
+    path: Vec<String>,
+    comparison: Option<(CompareOp, Value)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let rest = match expression.strip_prefix('$') {
+            Some(rest) => rest,
+            // "items[0].id" ohne führendes `$.` ist auch erlaubt
+            None if expression.starts_with(['.', '[']) => expression,
+            None => return Self::parse(&format!("$.{}", expression)),
+        };
+
+        let chars: Vec<char> = rest.chars().collect();
+        let mut segments = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' if chars.get(i + 1) == Some(&'.') => {
+                    i += 2;
+                    let (name, next) = read_identifier(&chars, i);
+                    i = next;
+                    segments.push(Segment::Descendant((name != "*").then_some(name)));
+                }
+                '.' => {
+                    let (name, next) = read_identifier(&chars, i + 1);
+                    if name.is_empty() {
+                        return Err(format!("⚠ Ungültiger JSONPath: Name fehlt an Position {}", i + 1));
+                    }
+                    i = next;
+                    segments.push(if name == "*" { Segment::Wildcard } else { Segment::Key(name) });
+                }
+                '[' => {
+                    let end = find_bracket_end(&chars, i)
+                        .ok_or_else(|| "⚠ Ungültiger JSONPath: fehlende ']'".to_string())?;
+                    let content: String = chars[i + 1..end].iter().collect();
+                    segments.push(parse_bracket(content.trim())?);
+                    i = end + 1;
+                }
+                c => return Err(format!("⚠ Ungültiger JSONPath: unerwartetes Zeichen '{}'", c)),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn select<'v>(&self, root: &'v Value) -> Vec<&'v Value> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            current = current.into_iter().flat_map(|value| apply_segment(segment, value)).collect();
+        }
+        current
+    }
+}
+
+fn read_identifier(chars: &[char], start: usize) -> (String, usize) {
+    let mut end = start;
+    while end < chars.len() && !matches!(chars[end], '.' | '[') {
+        end += 1;
+    }
+    (chars[start..end].iter().collect::<String>().trim().to_string(), end)
+}
+
+/// Passende `]` unter Beachtung von Anführungszeichen und Klammern im Filter.
+fn find_bracket_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ']') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_bracket(content: &str) -> Result<Segment, String> {
+    if content == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    if let Some(filter) = content.strip_prefix('?') {
+        return parse_filter(filter.trim());
+    }
+    if !is_quoted(content) && content.contains(':') {
+        let parse_bound = |s: &str| -> Result<Option<i64>, String> {
+            let s = s.trim();
+            if s.is_empty() {
+                Ok(None)
+            } else {
+                s.parse().map(Some).map_err(|_| format!("⚠ Ungültiger Slice-Index '{}'", s))
+            }
+        };
+        let (start, end) = content.split_once(':').unwrap_or_default();
+        // Schrittweite (`[::2]`) wird nicht unterstützt
+        let end = end.split(':').next().unwrap_or_default();
+        return Ok(Segment::Slice(parse_bound(start)?, parse_bound(end)?));
+    }
+
+    let mut parts: Vec<Segment> = split_union(content)
+        .into_iter()
+        .map(|part| {
+            let part = part.trim();
+            if is_quoted(part) {
+                Ok(Segment::Key(part[1..part.len() - 1].to_string()))
+            } else {
+                part.parse()
+                    .map(Segment::Index)
+                    .map_err(|_| format!("⚠ Ungültiger JSONPath-Ausdruck '[{}]'", content))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(if parts.len() == 1 { parts.remove(0) } else { Segment::Union(parts) })
+}
+
+fn is_quoted(s: &str) -> bool {
+    s.len() >= 2 && ((s.starts_with('\'') && s.ends_with('\'')) || (s.starts_with('"') && s.ends_with('"')))
+}
+
+fn split_union(content: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in content.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ',') => {
+                parts.push(&content[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&content[start..]);
+    parts
+}
+
+/// `(@.a.b)`, `(@.a == 'x')`, `(@.n >= 3)` – nur ein Vergleich, keine `&&`/`||`.
+fn parse_filter(filter: &str) -> Result<Segment, String> {
+    let inner = filter
+        .strip_prefix('(')
+        .and_then(|f| f.strip_suffix(')'))
+        .ok_or_else(|| format!("⚠ Filter muss in Klammern stehen: '{}'", filter))?
+        .trim();
+
+    let operators = [
+        ("==", CompareOp::Eq),
+        ("!=", CompareOp::Ne),
+        ("<=", CompareOp::Le),
+        (">=", CompareOp::Ge),
+        ("<", CompareOp::Lt),
+        (">", CompareOp::Gt),
+    ];
+    let comparison = operators
+        .iter()
+        .find_map(|(token, op)| inner.split_once(token).map(|(left, right)| (left, *op, right)));
+
+    let (left, comparison) = match comparison {
+        Some((left, op, right)) => (left.trim(), Some((op, parse_literal(right.trim())?))),
+        None => (inner, None),
+    };
+
+    let path = left
+        .strip_prefix('@')
+        .ok_or_else(|| format!("⚠ Filter muss mit '@' beginnen: '{}'", left))?
+        .split('.')
+        .filter(|key| !key.is_empty())
+        .map(String::from)
+        .collect();
+
+    Ok(Segment::Filter(Filter { path, comparison }))
+}
+
+fn parse_literal(literal: &str) -> Result<Value, String> {
+    if literal.len() >= 2 && literal.starts_with('\'') && literal.ends_with('\'') {
+        return Ok(Value::String(literal[1..literal.len() - 1].to_string()));
+    }
+    serde_json::from_str(literal).map_err(|_| format!("⚠ Ungültiger Wert im Filter: '{}'", literal))
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => vec![],
+    }
+}
+
+fn descendants<'v>(value: &'v Value, out: &mut Vec<&'v Value>) {
+    out.push(value);
+    for child in children(value) {
+        descendants(child, out);
+    }
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let resolved = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&resolved).then_some(resolved as usize)
+}
+
+fn apply_segment<'v>(segment: &Segment, value: &'v Value) -> Vec<&'v Value> {
+    match segment {
+        Segment::Key(key) => value.get(key).into_iter().collect(),
+        Segment::Index(index) => match value {
+            Value::Array(items) => resolve_index(*index, items.len()).map(|i| &items[i]).into_iter().collect(),
+            _ => vec![],
+        },
+        Segment::Slice(start, end) => match value {
+            Value::Array(items) => {
+                let len = items.len() as i64;
+                let clamp = |bound: i64| (if bound < 0 { len + bound } else { bound }).clamp(0, len) as usize;
+                let from = start.map(clamp).unwrap_or(0);
+                let to = end.map(clamp).unwrap_or(items.len());
+                items.get(from..to.max(from)).map(|s| s.iter().collect()).unwrap_or_default()
+            }
+            _ => vec![],
+        },
+        Segment::Union(parts) => parts.iter().flat_map(|part| apply_segment(part, value)).collect(),
+        Segment::Wildcard => children(value),
+        Segment::Descendant(name) => {
+            let mut all = Vec::new();
+            descendants(value, &mut all);
+            match name {
+                Some(key) => all.into_iter().filter_map(|v| v.get(key)).collect(),
+                None => all.into_iter().skip(1).collect(),
+            }
+        }
+        Segment::Filter(filter) => children(value).into_iter().filter(|child| filter.matches(child)).collect(),
+    }
+}
+
+impl Filter {
+    fn matches(&self, value: &Value) -> bool {
+        let target = self.path.iter().try_fold(value, |current, key| current.get(key));
+        match (target, &self.comparison) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(actual), Some((op, expected))) => {
+                let ordering = compare_values(actual, expected);
+                match op {
+                    CompareOp::Eq => ordering == Some(Ordering::Equal),
+                    CompareOp::Ne => ordering != Some(Ordering::Equal),
+                    CompareOp::Lt => ordering == Some(Ordering::Less),
+                    CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    CompareOp::Gt => ordering == Some(Ordering::Greater),
+                    CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                }
+            }
+        }
+    }
+}
+
+/// Zahlen werden numerisch verglichen (1 == 1.0), Strings lexikografisch, alles andere nur auf Gleichheit.
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => (a == b).then_some(Ordering::Equal),
+    }
+}
+
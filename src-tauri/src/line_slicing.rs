@@ -0,0 +1,305 @@
+use fancy_regex::Regex;
+use serde::Deserialize;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{ProcessedText, TextProcessor};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SliceOptions {
+    /// Bereichsausdrücke, getrennt durch `;` oder Zeilenumbruch, z.B. `100-250; 1~10; /BEGIN/,/END/; last:20`
+    pub ranges: String,
+    /// Ausgewählte Zeilen entfernen statt behalten
+    pub invert: bool,
+    pub casesensitive: bool,
+    /// Jede Zeile mit ihrer ursprünglichen Zeilennummer versehen
+    pub number: bool,
+    /// Mindestbreite der Nummer (rechtsbündig); 0 = Breite der größten Zeilennummer
+    pub numberwidth: usize,
+    pub numberseparator: String,
+}
+
+impl Default for SliceOptions {
+    fn default() -> Self {
+        Self {
+            ranges: String::new(),
+            invert: false,
+            casesensitive: true,
+            number: false,
+            numberwidth: 0,
+            numberseparator: ": ".to_string(),
+        }
+    }
+}
+
+enum Address {
+    /// 1-basierte Zeilennummer
+    Line(usize),
+    Last,
+    Pattern(Regex),
+    /// `+N`: N Zeilen nach dem Bereichsanfang (nur als Bereichsende)
+    Offset(usize),
+}
+
+enum Selector {
+    Single(Address),
+    Range(Address, Address),
+    /// `first~step` wie bei GNU sed
+    Step(usize, usize),
+    Head(usize),
+    Tail(usize),
+}
+
+impl Address {
+    fn matches(&self, index: usize, line: &str, total: usize) -> bool {
+        match self {
+            Address::Line(n) => index + 1 == *n,
+            Address::Last => index + 1 == total,
+            Address::Pattern(regex) => regex.is_match(line).unwrap_or(false),
+            Address::Offset(_) => false,
+        }
+    }
+}
+
+fn parse_number(text: &str, expression: &str) -> Result<usize, String> {
+    text.trim()
+        .parse::<usize>()
+        .map_err(|_| format!("⚠ Ungültiger Bereich '{}'", expression))
+}
+
+/// Liest eine Adresse am Anfang von `rest` und gibt den Rest zurück.
+fn parse_address<'a>(rest: &'a str, expression: &str, casesensitive: bool) -> Result<(Address, &'a str), String> {
+    let rest = rest.trim_start();
+    if let Some(body) = rest.strip_prefix('/') {
+        // Regex bis zum nächsten nicht escapten `/`
+        let mut escaped = false;
+        let end = body
+            .char_indices()
+            .find(|&(_, c)| {
+                let done = c == '/' && !escaped;
+                escaped = c == '\\' && !escaped;
+                done
+            })
+            .map(|(i, _)| i)
+            .ok_or_else(|| format!("⚠ Fehlendes abschließendes '/' in '{}'", expression))?;
+        let pattern = body[..end].replace("\\/", "/");
+        let regex = TextProcessor::build_filter_regex(&pattern, casesensitive)?;
+        return Ok((Address::Pattern(regex), &body[end + 1..]));
+    }
+    if let Some(after) = rest.strip_prefix('$') {
+        return Ok((Address::Last, after));
+    }
+
+    let (offset, digits_start) = match rest.strip_prefix('+') {
+        Some(after) => (true, after),
+        None => (false, rest),
+    };
+    let digits_len = digits_start.chars().take_while(char::is_ascii_digit).count();
+    if digits_len == 0 {
+        return Err(format!("⚠ Ungültiger Bereich '{}'", expression));
+    }
+    let number = parse_number(&digits_start[..digits_len], expression)?;
+    let after = &digits_start[digits_len..];
+    if offset {
+        return Ok((Address::Offset(number), after));
+    }
+    if number == 0 {
+        return Err(format!("⚠ Zeilen werden ab 1 gezählt ('{}')", expression));
+    }
+    Ok((Address::Line(number), after))
+}
+
+fn parse_selector(expression: &str, casesensitive: bool) -> Result<Selector, String> {
+    let lower = expression.to_lowercase();
+    for (keywords, tail) in [(["first:", "head:"], false), (["last:", "tail:"], true)] {
+        if let Some(count) = keywords.iter().find_map(|k| lower.strip_prefix(k)) {
+            let count = parse_number(count, expression)?;
+            return Ok(if tail { Selector::Tail(count) } else { Selector::Head(count) });
+        }
+    }
+
+    let (start, rest) = parse_address(expression, expression, casesensitive)?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        if matches!(start, Address::Offset(_)) {
+            return Err(format!("⚠ '+N' ist nur als Bereichsende erlaubt ('{}')", expression));
+        }
+        return Ok(Selector::Single(start));
+    }
+
+    if let Some(step) = rest.strip_prefix('~') {
+        let Address::Line(first) = start else {
+            return Err(format!("⚠ '~' braucht eine Zeilennummer als Anfang ('{}')", expression));
+        };
+        return Ok(Selector::Step(first, parse_number(step, expression)?));
+    }
+
+    let Some(end) = rest.strip_prefix(',').or_else(|| rest.strip_prefix('-')) else {
+        return Err(format!("⚠ Ungültiger Bereich '{}'", expression));
+    };
+    if matches!(start, Address::Offset(_)) {
+        return Err(format!("⚠ '+N' ist nur als Bereichsende erlaubt ('{}')", expression));
+    }
+    // `100-` bzw. `/x/,` reicht bis zum Ende
+    if end.trim().is_empty() {
+        return Ok(Selector::Range(start, Address::Last));
+    }
+    let (end, trailing) = parse_address(end, expression, casesensitive)?;
+    if !trailing.trim().is_empty() {
+        return Err(format!("⚠ Ungültiger Bereich '{}'", expression));
+    }
+    Ok(Selector::Range(start, end))
+}
+
+/// Zerlegt die Bereichsausdrücke; leere Angabe ist ein Fehler statt stillschweigend "nichts".
+fn parse_ranges(ranges: &str, casesensitive: bool) -> Result<Vec<Selector>, String> {
+    let selectors = ranges
+        .split([';', '\n'])
+        .map(str::trim)
+        .filter(|expression| !expression.is_empty())
+        .map(|expression| parse_selector(expression, casesensitive))
+        .collect::<Result<Vec<_>, _>>()?;
+    if selectors.is_empty() {
+        return Err("⚠ Kein Zeilenbereich angegeben".to_string());
+    }
+    Ok(selectors)
+}
+
+/// Markiert die Zeilen eines Selektors. Bereiche mit Regex-Anfang wiederholen sich wie bei sed,
+/// das Regex-Ende wird erst ab der Zeile nach dem Anfang gesucht.
+fn mark(selector: &Selector, lines: &[&str], selected: &mut [bool], op: &Operation) -> Result<(), OperationError> {
+    let total = lines.len();
+    match selector {
+        Selector::Head(count) => selected.iter_mut().take(*count).for_each(|s| *s = true),
+        Selector::Tail(count) => selected.iter_mut().skip(total.saturating_sub(*count)).for_each(|s| *s = true),
+        Selector::Step(first, step) => {
+            for (index, s) in selected.iter_mut().enumerate().skip(first - 1) {
+                if *step == 0 {
+                    *s = true;
+                    break;
+                }
+                if (index + 1 - first).is_multiple_of(*step) {
+                    *s = true;
+                }
+            }
+        }
+        Selector::Single(address) => {
+            for (index, line) in lines.iter().enumerate() {
+                op.progress(index, total)?;
+                if address.matches(index, line, total) {
+                    selected[index] = true;
+                }
+            }
+        }
+        Selector::Range(start, end) => {
+            let mut index = 0;
+            while index < total {
+                op.progress(index, total)?;
+                if !start.matches(index, lines[index], total) {
+                    index += 1;
+                    continue;
+                }
+                let last = match end {
+                    Address::Line(n) => (n - 1).max(index),
+                    Address::Last => total - 1,
+                    Address::Offset(n) => index.saturating_add(*n),
+                    Address::Pattern(_) => (index + 1..total)
+                        .find(|&i| end.matches(i, lines[i], total))
+                        .unwrap_or(total - 1),
+                }
+                .min(total - 1);
+                selected[index..=last].iter_mut().for_each(|s| *s = true);
+                index = last + 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Behält (oder entfernt mit `invert`) die Zeilen aus den Bereichsausdrücken, optional mit Zeilennummern.
+pub fn slice_lines(
+    text: &str,
+    options: &SliceOptions,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("slice_lines", text.len());
+
+    let selectors = parse_ranges(&options.ranges, options.casesensitive)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut selected = vec![false; total_lines];
+    for selector in &selectors {
+        mark(selector, &lines, &mut selected, op)?;
+    }
+
+    let width = match options.numberwidth {
+        0 => total_lines.to_string().len(),
+        width => width,
+    };
+    let result: Vec<String> = lines
+        .iter()
+        .zip(&selected)
+        .enumerate()
+        .filter(|(_, (_, &keep))| keep != options.invert)
+        .map(|(index, (line, _))| {
+            if options.number {
+                format!("{:>width$}{}{}", index + 1, options.numberseparator, line, width = width)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    let removed_lines = total_lines - result.len();
+    collector.phase("process");
+
+    let result_text = result.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_forms() {
+        assert!(matches!(parse_selector("5", true), Ok(Selector::Single(Address::Line(5)))));
+        assert!(matches!(parse_selector("$", true), Ok(Selector::Single(Address::Last))));
+        assert!(matches!(
+            parse_selector("100-250", true),
+            Ok(Selector::Range(Address::Line(100), Address::Line(250)))
+        ));
+        assert!(matches!(parse_selector("100-", true), Ok(Selector::Range(Address::Line(100), Address::Last))));
+        assert!(matches!(parse_selector("1~10", true), Ok(Selector::Step(1, 10))));
+        assert!(matches!(parse_selector("LAST:20", true), Ok(Selector::Tail(20))));
+        assert!(matches!(parse_selector("head:3", true), Ok(Selector::Head(3))));
+        assert!(matches!(
+            parse_selector("/BEGIN/,+3", true),
+            Ok(Selector::Range(Address::Pattern(_), Address::Offset(3)))
+        ));
+    }
+
+    #[test]
+    fn selector_pattern_with_escaped_slash() {
+        let Ok(Selector::Single(Address::Pattern(regex))) = parse_selector(r"/a\/b/", true) else {
+            panic!("Muster erwartet");
+        };
+        assert!(regex.is_match("xa/by").unwrap());
+    }
+
+    #[test]
+    fn selector_errors() {
+        for expression in ["0", "+3", "/open", "x", "5-7x", "$~2", "first:x"] {
+            assert!(parse_selector(expression, true).is_err(), "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use chrono::NaiveDateTime;
+use fancy_regex::{Captures, Regex};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+use crate::timestamps::{self, Zone};
+
+lazy_static! {
+    static ref GROK_REFERENCE: Regex = Regex::new(r"%\{(\w+)(?::([\w.\-]+))?\}").unwrap();
+    static ref LEVEL_WORD: Regex = Regex::new(
+        r"(?i)\b(emerg|emergency|alert|crit|critical|fatal|panic|err|error|severe|warn|warning|notice|info|information|debug|trace|verbose)\b"
+    )
+    .unwrap();
+}
+
+/// Bausteine für Grok-Muster (`%{NAME}` bzw. `%{NAME:feld}`), angelehnt an die Logstash-Standardmuster.
+const GROK_PATTERNS: [(&str, &str); 24] = [
+    ("INT", r"[+-]?\d+"),
+    ("POSINT", r"\d+"),
+    ("NUMBER", r"[+-]?(?:\d+(?:\.\d+)?|\.\d+)"),
+    ("WORD", r"\w+"),
+    ("NOTSPACE", r"\S+"),
+    ("SPACE", r"\s*"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+    ("QS", r#""(?:[^"\\]|\\.)*""#),
+    ("IPV4", r"(?:\d{1,3}\.){3}\d{1,3}"),
+    ("IPV6", r"[0-9A-Fa-f:]*:[0-9A-Fa-f:.]+"),
+    ("IP", r"%{IPV6}|%{IPV4}"),
+    ("HOSTNAME", r"[0-9A-Za-z][0-9A-Za-z\-_.]*"),
+    ("IPORHOST", r"%{IP}|%{HOSTNAME}"),
+    ("USER", r"[\w.@\-]+"),
+    ("PATH", r"/[^\s?#]*"),
+    ("URIPATHPARAM", r"\S+"),
+    ("MONTH", r"\b(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]*\b"),
+    ("HTTPDATE", r"\d{2}/%{MONTH}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}"),
+    ("SYSLOGTIMESTAMP", r"%{MONTH} +\d{1,2} \d{2}:\d{2}:\d{2}"),
+    (
+        "TIMESTAMP_ISO8601",
+        r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?",
+    ),
+    ("LOGLEVEL", r"(?i:emerg|alert|crit(?:ical)?|fatal|panic|err(?:or)?|warn(?:ing)?|notice|info|debug|trace)"),
+    ("PROG", r"[\w.\-/()]+"),
+    ("UUID", r"[0-9A-Fa-f]{8}-(?:[0-9A-Fa-f]{4}-){3}[0-9A-Fa-f]{12}"),
+];
+
+/// Gemeinsamer Anfang aller Access-Logs (`common`).
+macro_rules! access_log {
+    () => {
+        concat!(
+            r#"^%{IPORHOST:remote_addr} %{NOTSPACE:ident} %{NOTSPACE:remote_user} \[%{HTTPDATE:time}\] "#,
+            r#""(?:%{WORD:method} %{NOTSPACE:path}(?: HTTP/%{NUMBER:http_version})?|%{DATA:request})" "#,
+            r#"%{INT:status} (?:%{INT:bytes}|-)"#,
+        )
+    };
+}
+/// nginx schreibt standardmäßig `combined`, Referrer und User-Agent sind also immer dabei.
+const NGINX_ACCESS: &str = concat!(access_log!(), r#" "%{DATA:referrer}" "%{DATA:user_agent}""#);
+/// Apache nutzt je nach Konfiguration `common` oder `combined`.
+const APACHE_ACCESS: &str = concat!(access_log!(), r#"(?: "%{DATA:referrer}" "%{DATA:user_agent}")?\s*$"#);
+const NGINX_ERROR: &str =
+    r"^(?<time>\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}) \[%{LOGLEVEL:level}\] %{POSINT:pid}#%{POSINT:tid}: %{GREEDYDATA:message}";
+const SYSLOG: &str = concat!(
+    r"^(?:<%{POSINT:priority}>(?:1 )?)?(?<time>%{SYSLOGTIMESTAMP}|%{TIMESTAMP_ISO8601}) %{HOSTNAME:host} ",
+    r"%{PROG:program}(?:\[%{POSINT:pid}\])?: %{GREEDYDATA:message}",
+);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LogFormat {
+    /// Das Preset mit den meisten Treffern in den ersten Zeilen
+    #[default]
+    Auto,
+    /// nginx `combined` (Standard-Access-Log)
+    Nginx,
+    NginxError,
+    /// Apache `common` oder `combined`. `combined` sieht bei beiden Servern gleich aus und wird
+    /// von `auto` als nginx gemeldet, `common` als Apache.
+    Apache,
+    Syslog,
+    /// `journalctl -o json` oder die Kurzform von `journalctl`
+    Journald,
+    Json,
+    Logfmt,
+    /// Eigenes Grok-Muster aus `pattern`
+    Custom,
+}
+
+const PRESETS: [LogFormat; 7] = [
+    LogFormat::Syslog,
+    LogFormat::Journald,
+    LogFormat::Json,
+    LogFormat::Nginx,
+    LogFormat::NginxError,
+    LogFormat::Logfmt,
+    LogFormat::Apache,
+];
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogParseOptions {
+    pub format: LogFormat,
+    /// Grok-Muster für `custom`, z.B. `%{TIMESTAMP_ISO8601:time} %{LOGLEVEL:level} %{GREEDYDATA:message}`;
+    /// normale benannte Gruppen `(?<feld>...)` funktionieren ebenfalls
+    pub pattern: String,
+    /// Nur diese Level behalten (Synonyme wie `warning`/`warn` werden zusammengefasst)
+    pub levels: Vec<String>,
+    /// Zeitfenster (inklusive); Zeilen ohne lesbare Zeit fallen bei gesetztem Fenster heraus
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedLog {
+    /// Tatsächlich verwendetes Format (bei `auto` das erkannte)
+    pub format: LogFormat,
+    pub headers: Vec<String>,
+    /// Eine Zeichenkette pro Spalte, Zeilen mit `\n` getrennt – wie beim Split.
+    /// Zeilenumbrüche in Werten stehen als `\\n` bzw. `\\r` darin, damit die Zeilen ausgerichtet bleiben.
+    pub columns: Vec<String>,
+    pub records: usize,
+    /// Zeilennummern, die nicht zum Format passten
+    pub unparsed_lines: Vec<usize>,
+    pub filtered_out: usize,
+}
+
+/// Ersetzt `%{NAME}` und `%{NAME:feld}` durch die Regex-Bausteine (verschachtelt bis zu einer festen Tiefe).
+pub fn expand_grok(pattern: &str) -> Result<String, String> {
+    let mut expanded = pattern.to_string();
+    for _ in 0..8 {
+        if !GROK_REFERENCE.is_match(&expanded).unwrap_or(false) {
+            return Ok(expanded);
+        }
+        let mut error = None;
+        expanded = GROK_REFERENCE
+            .replace_all(&expanded, |caps: &Captures| {
+                let name = &caps[1];
+                let Some((_, regex)) = GROK_PATTERNS.iter().find(|(n, _)| *n == name) else {
+                    error = Some(format!("⚠ Unbekanntes Grok-Muster '%{{{}}}'", name));
+                    return String::new();
+                };
+                match caps.get(2) {
+                    Some(field) => format!("(?<{}>{})", field.as_str().replace(['.', '-'], "_"), regex),
+                    None => format!("(?:{})", regex),
+                }
+            })
+            .into_owned();
+        if let Some(error) = error {
+            return Err(error);
+        }
+    }
+    Err("⚠ Grok-Muster zu tief verschachtelt".to_string())
+}
+
+/// Vereinheitlicht Level-Namen, damit `WARNING`, `warn` und Syslog-Priorität 4 gleich gefiltert werden.
+fn normalize_level(level: &str) -> String {
+    let level = level.trim().to_lowercase();
+    let named = match level.as_str() {
+        "0" | "1" | "2" | "emerg" | "emergency" | "alert" | "crit" | "critical" | "fatal" | "panic" => "fatal",
+        "3" | "err" | "error" | "severe" => "error",
+        "4" | "warn" | "warning" => "warn",
+        "5" | "6" | "notice" | "info" | "information" | "informational" => "info",
+        "7" | "debug" => "debug",
+        "trace" | "verbose" => "trace",
+        _ => return level,
+    };
+    named.to_string()
+}
+
+/// Log-Zeiten ohne Offset gelten als UTC, damit gemischte Logs vergleichbar bleiben.
+fn parse_log_time(value: &str) -> Option<NaiveDateTime> {
+    timestamps::parse_timestamp(value, Zone::Utc).map(|dt| dt.naive_utc())
+}
+
+type Fields = Vec<(String, String)>;
+
+fn field<'a>(fields: &'a Fields, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .find_map(|name| fields.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Level eines Eintrags: Feld `level` (bzw. Synonyme), Syslog-Priorität, HTTP-Status oder ein Level-Wort in der Nachricht.
+fn record_level(fields: &Fields) -> Option<String> {
+    if let Some(level) = field(fields, &["level", "lvl", "severity", "log.level", "loglevel"]) {
+        return Some(normalize_level(level));
+    }
+    if let Some(priority) = field(fields, &["priority"]).and_then(|p| p.parse::<u32>().ok()) {
+        return Some(normalize_level(&(priority % 8).to_string()));
+    }
+    if let Some(status) = field(fields, &["status"]).and_then(|s| s.parse::<u32>().ok()) {
+        let level = match status {
+            500.. => "error",
+            400..=499 => "warn",
+            _ => "info",
+        };
+        return Some(level.to_string());
+    }
+    let message = field(fields, &["message", "msg"])?;
+    LEVEL_WORD
+        .captures(message)
+        .ok()
+        .flatten()
+        .map(|caps| normalize_level(&caps[1]))
+}
+
+fn record_time(fields: &Fields) -> Option<NaiveDateTime> {
+    field(fields, &["time", "timestamp", "ts", "@timestamp", "datetime", "date"]).and_then(parse_log_time)
+}
+
+fn flatten_json(prefix: &str, value: &Value, fields: &mut Fields) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_json(&name, value, fields);
+            }
+        }
+        Value::String(s) => fields.push((prefix.to_string(), s.clone())),
+        Value::Null => fields.push((prefix.to_string(), String::new())),
+        other => fields.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn parse_json_line(line: &str) -> Option<Fields> {
+    let value: Value = serde_json::from_str(line.trim()).ok()?;
+    if !value.is_object() {
+        return None;
+    }
+    let mut fields = Vec::new();
+    flatten_json("", &value, &mut fields);
+    Some(fields)
+}
+
+/// journald-Export: die wichtigsten Felder unter lesbaren Namen vorne, der Rest unverändert dahinter.
+fn parse_journald_line(line: &str, syslog: &Regex) -> Option<Fields> {
+    if !line.trim_start().starts_with('{') {
+        return parse_with_regex(line, syslog);
+    }
+    let mut raw = parse_json_line(line)?;
+    if !raw.iter().any(|(key, _)| key == "MESSAGE" || key == "__REALTIME_TIMESTAMP") {
+        return None;
+    }
+    let mut fields = Vec::new();
+    for (name, key) in [
+        ("time", "__REALTIME_TIMESTAMP"),
+        ("priority", "PRIORITY"),
+        ("host", "_HOSTNAME"),
+        ("program", "SYSLOG_IDENTIFIER"),
+        ("unit", "_SYSTEMD_UNIT"),
+        ("pid", "_PID"),
+        ("message", "MESSAGE"),
+    ] {
+        if let Some(position) = raw.iter().position(|(k, _)| k == key) {
+            let (_, value) = raw.remove(position);
+            let value = match name {
+                "time" => parse_log_time(&value).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()).unwrap_or(value),
+                _ => value,
+            };
+            fields.push((name.to_string(), value));
+        }
+    }
+    fields.extend(raw);
+    Some(fields)
+}
+
+/// `key=value key2="mit Leerzeichen"`; Zeilen ohne ein einziges Paar gelten als nicht erkannt.
+fn parse_logfmt_line(line: &str) -> Option<Fields> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let key_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = &rest[key_end..];
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            if let Some(quoted) = after.strip_prefix('"') {
+                let mut value = String::new();
+                let mut chars = quoted.char_indices();
+                let mut end = quoted.len();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => match chars.next().map(|(_, c)| c) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some(c @ ('"' | '\\')) => value.push(c),
+                            // Unbekannte Escapes bleiben wörtlich stehen
+                            Some(c) => {
+                                value.push('\\');
+                                value.push(c);
+                            }
+                            None => value.push('\\'),
+                        },
+                        '"' => {
+                            end = i + 1;
+                            break;
+                        }
+                        c => value.push(c),
+                    }
+                }
+                rest = &quoted[end..];
+                value
+            } else {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                rest = &after[end..];
+                after[..end].to_string()
+            }
+        } else {
+            // Schlüssel ohne Wert (`flag`) ist in logfmt erlaubt
+            String::new()
+        };
+        if key.is_empty() {
+            return None;
+        }
+        fields.push((key.to_string(), value));
+        rest = rest.trim_start();
+    }
+    fields.iter().any(|(_, value)| !value.is_empty()).then_some(fields)
+}
+
+fn parse_with_regex(line: &str, regex: &Regex) -> Option<Fields> {
+    let caps = regex.captures(line).ok()??;
+    Some(
+        regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| caps.name(name).map(|m| (name.to_string(), m.as_str().to_string())))
+            .collect(),
+    )
+}
+
+/// Wandelt eine Zeile je nach Format in Felder um.
+struct LineParser {
+    format: LogFormat,
+    regex: Option<Regex>,
+    syslog: Regex,
+}
+
+impl LineParser {
+    fn new(format: LogFormat, pattern: &str) -> Result<Self, String> {
+        let compile = |grok: &str| -> Result<Regex, String> {
+            Regex::new(&expand_grok(grok)?).map_err(|e| format!("⚠ Ungültiges Log-Muster: {}", e))
+        };
+        let regex = match format {
+            LogFormat::Nginx => Some(compile(NGINX_ACCESS)?),
+            LogFormat::Apache => Some(compile(APACHE_ACCESS)?),
+            LogFormat::NginxError => Some(compile(NGINX_ERROR)?),
+            LogFormat::Syslog => Some(compile(SYSLOG)?),
+            LogFormat::Custom if pattern.trim().is_empty() => {
+                return Err("⚠ Für ein eigenes Format wird ein Muster benötigt".to_string())
+            }
+            LogFormat::Custom => Some(compile(pattern)?),
+            _ => None,
+        };
+        Ok(Self {
+            format,
+            regex,
+            syslog: compile(SYSLOG)?,
+        })
+    }
+
+    fn parse(&self, line: &str) -> Option<Fields> {
+        match (self.format, &self.regex) {
+            (LogFormat::Json, _) => parse_json_line(line),
+            (LogFormat::Logfmt, _) => parse_logfmt_line(line),
+            (LogFormat::Journald, _) => parse_journald_line(line, &self.syslog),
+            (_, Some(regex)) => parse_with_regex(line, regex),
+            (_, None) => None,
+        }
+    }
+}
+
+/// Probiert die Presets an den ersten nicht-leeren Zeilen; bei Gleichstand gewinnt das frühere (spezifischere).
+fn detect_format(lines: &[&str]) -> Result<LogFormat, String> {
+    let sample: Vec<&str> = lines.iter().copied().filter(|l| !l.trim().is_empty()).take(20).collect();
+    let mut best = (LogFormat::Logfmt, 0);
+    for format in PRESETS {
+        let parser = LineParser::new(format, "")?;
+        let hits = sample.iter().filter(|line| parser.parse(line).is_some()).count();
+        if hits > best.1 {
+            best = (format, hits);
+        }
+    }
+    if best.1 == 0 {
+        return Err("⚠ Log-Format nicht erkannt, bitte Format oder eigenes Muster angeben".to_string());
+    }
+    Ok(best.0)
+}
+
+/// Hält mehrzeilige Werte (JSON, logfmt) in einer Zeile, sonst verrutschen die Spalten gegeneinander.
+fn escape_line_breaks(value: &str) -> Cow<'_, str> {
+    if value.contains(['\n', '\r']) {
+        Cow::Owned(value.replace('\r', "\\r").replace('\n', "\\n"))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Zerlegt jede Logzeile in Felder und liefert sie spaltenweise (wie der Split); optional nach Level und Zeit gefiltert.
+pub fn parse_logs(text: &str, options: &LogParseOptions, op: &Operation) -> Result<ProcessedText<ParsedLog>, OperationError> {
+    let mut collector = MetricsCollector::start("parse_logs", text.len());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    let format = match options.format {
+        LogFormat::Auto if !options.pattern.trim().is_empty() => LogFormat::Custom,
+        LogFormat::Auto => detect_format(&lines)?,
+        format => format,
+    };
+    let parser = LineParser::new(format, &options.pattern)?;
+    let levels: Vec<String> = options.levels.iter().map(|l| normalize_level(l)).collect();
+    let bound = |value: &Option<String>| -> Result<Option<NaiveDateTime>, String> {
+        match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            Some(v) => parse_log_time(v).map(Some).ok_or_else(|| format!("⚠ Unbekanntes Zeitformat '{}'", v)),
+            None => Ok(None),
+        }
+    };
+    let (from, to) = (bound(&options.from)?, bound(&options.to)?);
+    collector.phase("parse");
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut records: Vec<Fields> = Vec::new();
+    let mut unparsed_lines = Vec::new();
+    let mut filtered_out = 0;
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(fields) = parser.parse(line) else {
+            unparsed_lines.push(index + 1);
+            continue;
+        };
+
+        let level_ok = levels.is_empty() || record_level(&fields).is_some_and(|level| levels.contains(&level));
+        let time_ok = (from.is_none() && to.is_none())
+            || record_time(&fields)
+                .is_some_and(|time| from.is_none_or(|from| time >= from) && to.is_none_or(|to| time <= to));
+        if !level_ok || !time_ok {
+            filtered_out += 1;
+            continue;
+        }
+
+        for (key, _) in &fields {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+        records.push(fields);
+    }
+    collector.phase("process");
+
+    let columns: Vec<String> = headers
+        .iter()
+        .map(|header| {
+            records
+                .iter()
+                .map(|fields| escape_line_breaks(field(fields, &[header]).unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+    collector.phase("serialize");
+    let metrics = collector.finish(columns.iter().map(String::len).sum());
+
+    Ok(ProcessedText {
+        result_text: ParsedLog {
+            format,
+            headers,
+            columns,
+            records: records.len(),
+            unparsed_lines,
+            filtered_out,
+        },
+        removed_lines: total_lines - records.len(),
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMON: &str = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#;
+    const COMBINED: &str = concat!(
+        r#"127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.1" 200 612 "-" "#,
+        r#""Mozilla/5.0 (X11; Linux x86_64)""#
+    );
+
+    fn parse(text: &str, format: LogFormat) -> ParsedLog {
+        let options = LogParseOptions { format, ..Default::default() };
+        parse_logs(text, &options, &Operation::untracked()).unwrap().result_text
+    }
+
+    fn column<'a>(log: &'a ParsedLog, header: &str) -> &'a str {
+        let index = log.headers.iter().position(|h| h == header).expect("Spalte fehlt");
+        &log.columns[index]
+    }
+
+    #[test]
+    fn access_log_detection() {
+        assert_eq!(parse(COMMON, LogFormat::Auto).format, LogFormat::Apache);
+        assert_eq!(parse(COMBINED, LogFormat::Auto).format, LogFormat::Nginx);
+
+        let apache = parse(&format!("{}\n{}", COMMON, COMBINED), LogFormat::Apache);
+        assert!(apache.unparsed_lines.is_empty());
+        let nginx = parse(&format!("{}\n{}", COMMON, COMBINED), LogFormat::Nginx);
+        assert_eq!(nginx.unparsed_lines, vec![1]);
+        assert_eq!(column(&nginx, "user_agent"), "Mozilla/5.0 (X11; Linux x86_64)");
+    }
+
+    #[test]
+    fn logfmt_escapes() {
+        let fields = parse_logfmt_line(r#"a="x\ny" b="tab\there" c="\"q\" \\ \d""#).unwrap();
+        assert_eq!(field(&fields, &["a"]), Some("x\ny"));
+        assert_eq!(field(&fields, &["b"]), Some("tab\there"));
+        assert_eq!(field(&fields, &["c"]), Some(r#""q" \ \d"#));
+    }
+
+    #[test]
+    fn multiline_values_keep_rows_aligned() {
+        let log = parse("level=info msg=\"eins\\nzwei\"\nlevel=warn msg=drei", LogFormat::Logfmt);
+        assert_eq!(log.records, 2);
+        assert_eq!(column(&log, "msg"), "eins\\nzwei\ndrei");
+        assert_eq!(column(&log, "level").lines().count(), column(&log, "msg").lines().count());
+    }
+}
@@ -4,123 +4,841 @@
 use settimeout::set_timeout;
 use std::time::Duration;
 
-use tauri::{Manager, State, WindowEvent};
+use tauri::{Emitter, Manager, State, WindowEvent};
 
 mod text_processor;
 mod memory_usage;
-use text_processor::{ProcessedText, TextProcessor};
+mod metrics;
+use metrics::{BackendMetrics, TrackingAllocator};
+
+mod benchmark;
+use benchmark::{BenchmarkRequest, BenchmarkResult};
+use text_processor::{ConvertedLineEndings, LineEndingInfo, ProcessedText, TextProcessor};
+
+mod file_processor;
+use file_processor::FileResult;
+
+mod file_encoding;
+use file_encoding::{LoadedFile, SaveReport};
+
+mod operations;
+use operations::{Operation, OperationError};
 
 mod text_diff;
 
+mod terminal_cleanup;
+use terminal_cleanup::CleanedText;
+
+mod format_conversion;
+use format_conversion::ConversionOptions;
+
+mod column_projection;
+
+mod column_stats;
+use column_stats::ColumnStats;
+
+mod hashing;
+use hashing::HashOptions;
+
+mod records;
+use records::RecordSplit;
+
+mod line_slicing;
+use line_slicing::SliceOptions;
+
+mod transforms;
+use transforms::{TransformDirection, TransformKind, TransformResult};
+
+mod text_stats;
+use text_stats::{StatsOptions, TextStats};
+
+mod log_parsing;
+use log_parsing::{LogParseOptions, ParsedLog};
+
+mod timestamps;
+use timestamps::TimestampOptions;
+
+mod json_tools;
+use json_tools::JsonValidation;
+
+mod unicode_inspector;
+use unicode_inspector::{InspectionReport, NormalizeOptions};
+
+// Recipes
+mod recipes;
+use recipes::Recipe;
+use std::collections::HashMap;
+
 // Settings
+mod pattern_library;
+use pattern_library::{PatternValidation, SavedPattern};
+mod profiles;
+use profiles::PagePreset;
 mod settings;
-use settings::SaveAppSettings;
+use settings::{SaveAppSettings, SettingsLoadReport};
+mod settings_transfer;
+use settings_transfer::{ConflictStrategy, MergeSummary, SettingsExport, SettingsSection};
 use std::sync::{Arc, Mutex};
 
-use lazy_static::lazy_static;
-use memory_stats::{memory_stats, MemoryStats};
-
-
 // Wrap `SaveAppSettings` in `Arc<Mutex<T>>` für sichere Thread-Nutzung
 pub struct SettingsState(pub Arc<Mutex<SaveAppSettings>>);
 
-lazy_static! {
-    static ref MEMORY_START: Mutex<Option<MemoryStats>> = Mutex::new(None);
-}
+// Ergebnis des Ladens beim Start (Migration, beschädigte Datei, ...)
+pub struct SettingsLoadState(pub SettingsLoadReport);
+
+// Zählt Allokationen pro Thread für die Metriken der einzelnen Commands
+#[global_allocator]
+static GLOBAL: TrackingAllocator = TrackingAllocator;
 
 #[tauri::command]
-fn start_memory_measurement() {
-    let mut mem_start = MEMORY_START.lock().unwrap();
-    *mem_start = memory_stats(); // Ersten Messwert speichern
+fn is_backend_available() -> bool {
+    true // Immer verfügbar, solange Tauri läuft
 }
 
-#[tauri::command]
-fn get_memory_diff() -> Option<(i64, i64)> {
-    let mut mem_start = MEMORY_START.lock().unwrap();
-    if let Some(start_mem) = *mem_start {
-        if let Some(current_mem) = memory_stats() {
-            let phys_diff = (current_mem.physical_mem as i64) - (start_mem.physical_mem as i64);
-            let virt_diff = (current_mem.virtual_mem as i64) - (start_mem.virtual_mem as i64);
-            *mem_start = None; // Nach der Messung zurücksetzen
-            return Some((phys_diff, virt_diff));
+/// Startet eine Operation, deren Fortschritt als `operation-progress` Event ans Frontend geht.
+/// Mit einer `operationid` kann sie über `cancel_operation` abgebrochen werden.
+fn start_operation(app: &tauri::AppHandle, operationid: Option<String>, name: &'static str) -> Operation {
+    let app = app.clone();
+    Operation::start(
+        operationid,
+        name,
+        Some(Box::new(move |progress| {
+            let _ = app.emit("operation-progress", progress);
+        })),
+    )
+}
+
+/// Textoperationen sind CPU-lastig und laufen deshalb im Blocking-Pool, damit Fortschritt und Abbruch durchkommen.
+/// Die Operation wird vorher registriert, damit ein Abbruch vor dem Start des Tasks nicht verloren geht.
+async fn run_blocking<T, F>(op: Operation, task: F) -> Result<T, OperationError>
+where
+    T: Send + 'static,
+    F: FnOnce(&Operation) -> Result<T, OperationError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = task(&op);
+        if result.is_ok() {
+            op.complete();
         }
-    }
-    None
+        result
+    })
+    .await
+    .map_err(|e| OperationError::Failed(e.to_string()))?
 }
 
 #[tauri::command]
-fn is_backend_available() -> bool {
-    true // Immer verfügbar, solange Tauri läuft
+fn cancel_operation(operationid: String) -> bool {
+    operations::cancel_operation(&operationid)
 }
 
 #[tauri::command]
 async fn process_text_block(
+    app: tauri::AppHandle,
+    text: String,
+    delimiter: String,
+    trimparts: bool,
+    useregex: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<Vec<String>>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "split"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::process_text_block_to_column_strings(&text, &delimiter, trimparts, useregex, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+fn detect_line_endings(text: String) -> LineEndingInfo {
+    TextProcessor::detect_line_endings(&text)
+}
+
+#[tauri::command]
+async fn convert_line_endings(
+    app: tauri::AppHandle,
+    text: String,
+    target: String,
+    operationid: Option<String>,
+) -> Result<ProcessedText<ConvertedLineEndings>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "convert_line_endings"), move |op| {
+        TextProcessor::convert_line_endings(&text, &target, op)
+    })
+    .await
+}
+
+/// Öffnet eine Textdatei in beliebiger Kodierung; die erkannte Kodierung wird für `save_text_file` gemerkt.
+#[tauri::command]
+async fn open_text_file(filepath: String) -> Result<LoadedFile, String> {
+    tauri::async_runtime::spawn_blocking(move || file_encoding::load_file(&filepath))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn save_text_file(
+    filepath: String,
     text: String,
+    encoding: Option<String>,
+    bom: Option<bool>,
+) -> Result<SaveReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        file_encoding::save_file(&filepath, &text, encoding.as_deref(), bom)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn close_text_file(filepath: String) {
+    file_encoding::forget_file(&filepath);
+}
+
+#[tauri::command]
+fn get_supported_encodings() -> Vec<&'static str> {
+    file_encoding::SUPPORTED_ENCODINGS.to_vec()
+}
+
+/// Datei-Varianten: lesen die Datei zeilenweise und schreiben das Ergebnis nach `outputpath`,
+/// damit große Dateien nicht komplett über IPC geschickt werden müssen.
+#[tauri::command]
+async fn split_file(
+    app: tauri::AppHandle,
+    inputpath: String,
+    outputpath: String,
     delimiter: String,
+    outputseparator: String,
     trimparts: bool,
     useregex: bool,
-) -> Result<ProcessedText<Vec<String>>, String> {
-    TextProcessor::process_text_block_to_column_strings(&text, &delimiter, trimparts, useregex)
-        .map_err(|e| e.to_string())
+    operationid: Option<String>,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let op = start_operation(&app, operationid, "split");
+    tauri::async_runtime::spawn_blocking(move || {
+        file_processor::split_file(
+            &inputpath,
+            &outputpath,
+            &delimiter,
+            &outputseparator,
+            trimparts,
+            useregex,
+            &op,
+        )
+    })
+    .await
+    .map_err(|e| OperationError::Failed(e.to_string()))?
+}
+
+#[tauri::command]
+async fn filter_file(
+    app: tauri::AppHandle,
+    inputpath: String,
+    outputpath: String,
+    pattern: String,
+    filtermode: String,
+    casesensitive: bool,
+    splitmatches: bool,
+    operationid: Option<String>,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let op = start_operation(&app, operationid, "filter");
+    tauri::async_runtime::spawn_blocking(move || {
+        file_processor::filter_file(
+            &inputpath,
+            &outputpath,
+            &pattern,
+            &filtermode,
+            casesensitive,
+            splitmatches,
+            &op,
+        )
+    })
+    .await
+    .map_err(|e| OperationError::Failed(e.to_string()))?
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn remove_lines_file(
+    app: tauri::AppHandle,
+    inputpath: String,
+    outputpath: String,
+    pattern: String,
+    removemode: String,
+    casesensitive: bool,
+    useregex: bool,
+    trimparts: bool,
+    operationid: Option<String>,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let op = start_operation(&app, operationid, "remove");
+    tauri::async_runtime::spawn_blocking(move || {
+        file_processor::remove_lines_file(
+            &inputpath,
+            &outputpath,
+            &pattern,
+            &removemode,
+            casesensitive,
+            useregex,
+            trimparts,
+            &op,
+        )
+    })
+    .await
+    .map_err(|e| OperationError::Failed(e.to_string()))?
 }
 
-// #[tauri::command]
-// async fn process_file(file_path: String, delimiter: String) -> Result<Vec<String>, String> {
-//     TextProcessor::process_file_to_column_strings(file_path, &delimiter)
-//         .map_err(|err| format!("Error processing file: {}", err))
-// }
+#[tauri::command]
+async fn sort_file(
+    app: tauri::AppHandle,
+    inputpath: String,
+    outputpath: String,
+    sortmode: String,
+    casesensitive: bool,
+    operationid: Option<String>,
+) -> Result<ProcessedText<FileResult>, OperationError> {
+    let op = start_operation(&app, operationid, "sort");
+    tauri::async_runtime::spawn_blocking(move || {
+        file_processor::sort_file(
+            &inputpath,
+            &outputpath,
+            &sortmode,
+            casesensitive,
+            &op,
+        )
+    })
+    .await
+    .map_err(|e| OperationError::Failed(e.to_string()))?
+}
 
+/// Das Ergebnis übernimmt den Zeilenende-Stil des ersten Texts.
 #[tauri::command]
-async fn connect_texts(text1: String, text2: String, separator: String) -> Result<String, String> {
-    TextProcessor::connect_texts(&text1, &text2, &separator).map_err(|e| e.to_string())
+async fn connect_texts(
+    text1: String,
+    text2: String,
+    separator: String,
+    preservelineendings: Option<bool>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (text1, endings) = TextProcessor::normalize_line_endings(&text1);
+        let (text2, _) = TextProcessor::normalize_line_endings(&text2);
+        let result = TextProcessor::connect_texts(&text1, &text2, &separator)?;
+        Ok(if preservelineendings.unwrap_or(true) {
+            TextProcessor::restore_line_endings(&result, &endings)
+        } else {
+            result
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Text Diff Method
 #[tauri::command]
 async fn get_text_diff(
+    app: tauri::AppHandle,
     oldtext: String,
     newtext: String,
     ignorewhitespace: bool,
-) -> Result<ProcessedText<String>, String> {
-    text_diff::get_diff_json(&oldtext, &newtext, ignorewhitespace).map_err(|e| e.to_string())
+    operationid: Option<String>,
+    normalize: Option<NormalizeOptions>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "diff"), move |op| {
+        let (oldtext, _) = TextProcessor::normalize_line_endings(&oldtext);
+        let (newtext, _) = TextProcessor::normalize_line_endings(&newtext);
+        text_diff::get_diff_json(&oldtext, &newtext, ignorewhitespace, normalize.as_ref(), op)
+    })
+    .await
+}
+
+/// Entfernt ANSI-Farben/Escape-Sequenzen aus eingefügter Terminal- oder CI-Ausgabe
+#[tauri::command]
+async fn clean_terminal_output(
+    app: tauri::AppHandle,
+    text: String,
+    removeprogress: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<CleanedText>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "clean_terminal"), move |op| {
+        // Nicht normalisieren: ein einzelnes `\r` ist hier Überschreiben und kein Zeilenende
+        let endings = terminal_cleanup::detect_line_endings(&text);
+        terminal_cleanup::clean_terminal_output(&text, removeprogress, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Findet unsichtbare Zeichen, Steuerzeichen und Confusables (z.B. kyrillisches "а" statt "a")
+#[tauri::command]
+async fn inspect_characters(
+    app: tauri::AppHandle,
+    text: String,
+    operationid: Option<String>,
+) -> Result<ProcessedText<InspectionReport>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "inspect_characters"), move |op| {
+        unicode_inspector::inspect_characters(&text, op)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn normalize_text(
+    app: tauri::AppHandle,
+    text: String,
+    options: NormalizeOptions,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "normalize_text"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        unicode_inspector::normalize_text(&text, &options, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Wandelt Spalten-Text (oder JSON) in JSON, JSON Lines, Markdown, ASCII, HTML, SQL oder CSV um
+#[tauri::command]
+async fn convert_format(
+    app: tauri::AppHandle,
+    text: String,
+    options: ConversionOptions,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "convert_format"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        format_conversion::convert_format(&text, &options, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Base64, URL, Hex, HTML-Entities, Quoted-Printable und `\uXXXX` kodieren/dekodieren, ganz oder zeilenweise
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn transform_text(
+    app: tauri::AppHandle,
+    text: String,
+    kind: TransformKind,
+    direction: TransformDirection,
+    perline: Option<bool>,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<TransformResult>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "transform_text"), move |op| {
+        if !perline.unwrap_or(false) {
+            // Ganzer Text: Kodieren sieht die Originalbytes, Dekodieren behält die Zeilenenden der Eingabe
+            return transforms::transform_text(&text, kind, direction, false, op);
+        }
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        transforms::transform_text(&text, kind, direction, true, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// MD5, SHA-1/256/512, BLAKE3, CRC32 oder xxHash über den ganzen Text oder pro Zeile/Spalte
+#[tauri::command]
+async fn hash_text(
+    app: tauri::AppHandle,
+    text: String,
+    options: HashOptions,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "hash_text"), move |op| {
+        if !options.perline {
+            // Der Hash über den ganzen Text muss die Originalbytes sehen und ist selbst einzeilig
+            return hashing::hash_text(&text, &options, op);
+        }
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        hashing::hash_text(&text, &options, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Logzeilen (nginx, Apache, Syslog, journald, JSON, logfmt oder eigenes Grok-Muster) als Spalten wie beim Split
+#[tauri::command]
+async fn parse_logs(
+    app: tauri::AppHandle,
+    text: String,
+    options: Option<LogParseOptions>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<ParsedLog>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "parse_logs"), move |op| {
+        log_parsing::parse_logs(&text, &options.unwrap_or_default(), op)
+    })
+    .await
+}
+
+/// Zeilen-, Wort-, Zeichen-, Graphem- und Byte-Zahlen, Zeilenlängen sowie Wort- und N-Gramm-Häufigkeiten
+#[tauri::command]
+async fn analyze_text(
+    app: tauri::AppHandle,
+    text: String,
+    options: Option<StatsOptions>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<TextStats>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "analyze_text"), move |op| {
+        text_stats::analyze_text(&text, &options.unwrap_or_default(), op)
+    })
+    .await
+}
+
+/// Spaltenauswahl wie bei `cut`: `1,3,5-7`, negative Indizes und Spaltennamen; mit `drop` werden sie entfernt
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn project_columns(
+    app: tauri::AppHandle,
+    text: String,
+    columns: String,
+    drop: Option<bool>,
+    options: Option<ConversionOptions>,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "project_columns"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        column_projection::project_columns(&text, &columns, drop.unwrap_or(false), &options.unwrap_or_default(), op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn transpose_columns(
+    app: tauri::AppHandle,
+    text: String,
+    options: Option<ConversionOptions>,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "transpose"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        column_projection::transpose(&text, &options.unwrap_or_default(), op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Erkennt pro Spalte den Typ und liefert Null-/Distinct-Zähler, Min/Max, Mittelwert/Median und die häufigsten Werte
+#[tauri::command]
+async fn analyze_columns(
+    app: tauri::AppHandle,
+    text: String,
+    options: Option<ConversionOptions>,
+    top: Option<usize>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<Vec<ColumnStats>>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "analyze_columns"), move |op| {
+        column_stats::analyze_columns(&text, &options.unwrap_or_default(), top.unwrap_or(10), op)
+    })
+    .await
+}
+
+/// JSON Methods: `jsonlines` = None erkennt JSON Lines automatisch
+#[tauri::command]
+async fn pretty_print_json(
+    app: tauri::AppHandle,
+    text: String,
+    indent: usize,
+    sortkeys: bool,
+    jsonlines: Option<bool>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "pretty_print_json"), move |op| {
+        json_tools::pretty_print(&text, indent, sortkeys, jsonlines, op)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn minify_json(
+    app: tauri::AppHandle,
+    text: String,
+    sortkeys: bool,
+    jsonlines: Option<bool>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "minify_json"), move |op| {
+        json_tools::minify(&text, sortkeys, jsonlines, op)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn validate_json(
+    app: tauri::AppHandle,
+    text: String,
+    jsonlines: Option<bool>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<JsonValidation>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "validate_json"), move |op| {
+        json_tools::validate(&text, jsonlines, op)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn extract_json(
+    app: tauri::AppHandle,
+    text: String,
+    path: String,
+    raw: bool,
+    jsonlines: Option<bool>,
+    operationid: Option<String>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "extract_json"), move |op| {
+        json_tools::extract(&text, &path, raw, jsonlines, op)
+    })
+    .await
 }
 
 /// Filter Text Method
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-fn filter_text(
+async fn filter_text(
+    app: tauri::AppHandle,
     text: String,
     pattern: String,
     filtermode: String,
     casesensitive: bool,
     splitmatches: bool,
-) -> Result<ProcessedText<String>, String> {
-    TextProcessor::filter_text_internal(
-        &text,
-        &pattern,
-        &filtermode,
-        casesensitive,
-        splitmatches,
-    )
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+    records: Option<RecordSplit>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "filter"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        match &records {
+            Some(split) => records::filter_records(&text, split, &pattern, &filtermode, casesensitive, op),
+            None => TextProcessor::filter_text_internal(
+                &text,
+                &pattern,
+                &filtermode,
+                casesensitive,
+                splitmatches,
+                op,
+            ),
+        }
+        .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Sort Lines Method
+#[tauri::command]
+async fn sort_text(
+    app: tauri::AppHandle,
+    text: String,
+    sortmode: String,
+    casesensitive: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+    records: Option<RecordSplit>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "sort"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        match &records {
+            Some(split) => records::sort_records(&text, split, &sortmode, casesensitive, op),
+            None => TextProcessor::sort_lines_internal(&text, &sortmode, casesensitive, op),
+        }
+        .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Zeilenbereiche: `100-250`, `1~10`, `first:N`, `last:N`, `/start/,/ende/`, optional mit Zeilennummern
+#[tauri::command]
+async fn slice_lines(
+    app: tauri::AppHandle,
+    text: String,
+    options: SliceOptions,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "slice_lines"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        line_slicing::slice_lines(&text, &options, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Zeitstempel erkennen, in ein anderes Format bzw. eine andere Zeitzone umschreiben, Zeilen danach sortieren oder filtern
+#[tauri::command]
+async fn process_timestamps(
+    app: tauri::AppHandle,
+    text: String,
+    options: TimestampOptions,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "process_timestamps"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        timestamps::process_timestamps(&text, &options, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Wrap / Reflow / Prefix / Tabs Methods
+#[tauri::command]
+async fn wrap_text(
+    app: tauri::AppHandle,
+    text: String,
+    width: usize,
+    breakwords: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "wrap_text"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::wrap_lines(&text, width, breakwords, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn reflow_text(
+    app: tauri::AppHandle,
+    text: String,
+    width: usize,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "reflow_text"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::reflow_paragraphs(&text, width, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn add_line_prefix(
+    app: tauri::AppHandle,
+    text: String,
+    prefix: String,
+    skipempty: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "add_prefix"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::add_line_prefix(&text, &prefix, skipempty, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn remove_line_prefix(
+    app: tauri::AppHandle,
+    text: String,
+    prefix: String,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "remove_prefix"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::remove_line_prefix(&text, &prefix, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn convert_tabs(
+    app: tauri::AppHandle,
+    text: String,
+    tabstop: usize,
+    totabs: bool,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "convert_tabs"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        TextProcessor::convert_tabs(&text, tabstop, totabs, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
 }
 
 /// Remove Lines Method
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-fn remove_lines(
+async fn remove_lines(
+    app: tauri::AppHandle,
     text: String,
     pattern: String,
     removemode: String,
     casesensitive: bool,
     useregex: bool,
     trimparts: bool,
-) -> Result<ProcessedText<String>, String> {
-    TextProcessor::remove_lines_internal(
-        &text,
-        &pattern,
-        &removemode,
-        casesensitive,
-        useregex,
-        trimparts,
-    )
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+    normalize: Option<NormalizeOptions>,
+    records: Option<RecordSplit>,
+) -> Result<ProcessedText<String>, OperationError> {
+    run_blocking(start_operation(&app, operationid, "remove"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        match &records {
+            Some(split) => records::remove_records(
+                &text,
+                split,
+                &pattern,
+                &removemode,
+                casesensitive,
+                useregex,
+                trimparts,
+                normalize.as_ref(),
+                op,
+            ),
+            None => TextProcessor::remove_lines_internal(
+                &text,
+                &pattern,
+                &removemode,
+                casesensitive,
+                useregex,
+                trimparts,
+                normalize.as_ref(),
+                op,
+            ),
+        }
+        .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+/// Metrics & Benchmark Methods
+#[tauri::command]
+fn get_metrics_history(command: Option<String>) -> Vec<BackendMetrics> {
+    metrics::get_history(command.as_deref())
+}
+
+#[tauri::command]
+fn clear_metrics_history() {
+    metrics::clear_history();
+}
+
+#[tauri::command]
+fn export_metrics_csv(command: Option<String>, filepath: String) -> Result<(), String> {
+    let csv = metrics::history_to_csv(&metrics::get_history(command.as_deref()));
+    std::fs::write(&filepath, csv).map_err(|e| format!("Fehler beim Exportieren der Metriken: {:?}", e))
+}
+
+#[tauri::command]
+async fn run_benchmark(
+    app: tauri::AppHandle,
+    request: BenchmarkRequest,
+    operationid: Option<String>,
+) -> Result<BenchmarkResult, OperationError> {
+    run_blocking(start_operation(&app, operationid, "benchmark"), move |op| {
+        benchmark::run_benchmark(&request, op)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -130,11 +848,147 @@ fn get_settings(state: State<SettingsState>) -> SaveAppSettings {
 
 
 #[tauri::command]
-fn set_settings(state: State<SettingsState>, newsettings: SaveAppSettings) -> Result<(), String> {
-    *state.0.lock().unwrap() = newsettings;
+fn get_settings_load_report(state: State<SettingsLoadState>) -> SettingsLoadReport {
+    state.0.clone()
+}
+
+#[tauri::command]
+fn set_settings(state: State<SettingsState>, mut newsettings: SaveAppSettings) -> Result<(), String> {
+    let mut settings = state.0.lock().unwrap();
+    newsettings.keep_backend_fields(&mut settings);
+    newsettings.sync_active_profile();
+    *settings = newsettings;
     Ok(())
 }
 
+/// Prüft alle gespeicherten Muster (oder die übergebenen) auf gültige Syntax und erwartete Treffer
+#[tauri::command]
+fn validate_patterns(state: State<SettingsState>, patterns: Option<Vec<SavedPattern>>) -> Vec<PatternValidation> {
+    let patterns = patterns.unwrap_or_else(|| state.0.lock().unwrap().saved_patterns.clone().unwrap_or_default());
+    pattern_library::validate_patterns(&patterns)
+}
+
+/// Profile Methods
+#[tauri::command]
+fn create_profile(state: State<SettingsState>, name: String) -> Result<SaveAppSettings, String> {
+    let mut settings = state.0.lock().unwrap();
+    settings.create_profile(&name)?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn clone_profile(state: State<SettingsState>, source: String, name: String) -> Result<SaveAppSettings, String> {
+    let mut settings = state.0.lock().unwrap();
+    settings.clone_profile(&source, &name)?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn switch_profile(state: State<SettingsState>, name: String) -> Result<SaveAppSettings, String> {
+    let mut settings = state.0.lock().unwrap();
+    settings.switch_profile(&name)?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn delete_profile(state: State<SettingsState>, name: String) -> Result<SaveAppSettings, String> {
+    let mut settings = state.0.lock().unwrap();
+    settings.delete_profile(&name)?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn export_settings(state: State<SettingsState>, filepath: String, sections: Vec<SettingsSection>) -> Result<(), String> {
+    let settings = state.0.lock().unwrap();
+    SettingsExport::from_settings(&settings, &sections).write(&filepath)
+}
+
+#[tauri::command]
+fn import_settings(
+    state: State<SettingsState>,
+    filepath: String,
+    strategy: ConflictStrategy,
+) -> Result<MergeSummary, String> {
+    let incoming = SettingsExport::read(&filepath)?;
+    let mut settings = state.0.lock().unwrap();
+    Ok(incoming.merge_into(&mut settings, strategy))
+}
+
+#[tauri::command]
+fn get_page_preset(state: State<SettingsState>, page: String) -> Option<PagePreset> {
+    state.0.lock().unwrap().page_preset(&page)
+}
+
+#[tauri::command]
+fn set_page_preset(state: State<SettingsState>, page: String, preset: PagePreset) {
+    state.0.lock().unwrap().set_page_preset(&page, preset);
+}
+
+
+/// Recipe Methods
+#[tauri::command]
+async fn run_recipe(
+    app: tauri::AppHandle,
+    state: State<'_, SettingsState>,
+    text: String,
+    name: String,
+    params: HashMap<String, String>,
+    operationid: Option<String>,
+    preservelineendings: Option<bool>,
+) -> Result<ProcessedText<String>, OperationError> {
+    let recipe = state
+        .0
+        .lock()
+        .unwrap()
+        .recipes
+        .iter()
+        .flatten()
+        .find(|r| r.name == name)
+        .cloned()
+        .ok_or_else(|| format!("⚠ Recipe '{}' nicht gefunden", name))?;
+
+    run_blocking(start_operation(&app, operationid, "recipe"), move |op| {
+        let (text, endings) = TextProcessor::normalize_line_endings(&text);
+        recipe
+            .run(&text, &params, op)
+            .map(|result| result.with_line_endings(&endings, preservelineendings.unwrap_or(true)))
+    })
+    .await
+}
+
+#[tauri::command]
+fn save_recipe(state: State<SettingsState>, recipe: Recipe) -> Result<(), String> {
+    let mut settings = state.0.lock().unwrap();
+    recipes::merge_recipes(settings.recipes.get_or_insert_with(Vec::new), vec![recipe]).map(|_| ())
+}
+
+#[tauri::command]
+fn delete_recipe(state: State<SettingsState>, name: String) {
+    if let Some(recipes) = state.0.lock().unwrap().recipes.as_mut() {
+        recipes.retain(|r| r.name != name);
+    }
+}
+
+#[tauri::command]
+fn export_recipes(state: State<SettingsState>, names: Vec<String>, filepath: String) -> Result<(), String> {
+    let settings = state.0.lock().unwrap();
+    let selected: Vec<Recipe> = settings
+        .recipes
+        .iter()
+        .flatten()
+        .filter(|r| names.is_empty() || names.contains(&r.name))
+        .cloned()
+        .collect();
+
+    recipes::export_recipes(&selected, &filepath)
+}
+
+#[tauri::command]
+fn import_recipes(state: State<SettingsState>, filepath: String) -> Result<Vec<String>, String> {
+    let incoming = recipes::import_recipes(&filepath)?;
+    let mut settings = state.0.lock().unwrap();
+    recipes::merge_recipes(settings.recipes.get_or_insert_with(Vec::new), incoming)
+}
 
 fn save_settings_to_file(state: Arc<Mutex<SaveAppSettings>>) -> Result<(), String> {
     state.lock().unwrap().save()
@@ -174,24 +1028,80 @@ fn main() {
     #[cfg(target_os = "linux")]
     std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
 
+    let (settings, load_report) = SaveAppSettings::load();
+    if let Some(error) = &load_report.error {
+        eprintln!("❌ Fehler beim Laden der Settings: {}", error);
+    }
+
     tauri::Builder::default()
-        .manage(SettingsState(Arc::new(Mutex::new(SaveAppSettings::load()))))
+        .manage(SettingsState(Arc::new(Mutex::new(settings))))
+        .manage(SettingsLoadState(load_report))
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .invoke_handler(tauri::generate_handler![
             get_settings,
             set_settings,
+            get_settings_load_report,
+            validate_patterns,
+            create_profile,
+            clone_profile,
+            switch_profile,
+            delete_profile,
+            get_page_preset,
+            set_page_preset,
+            export_settings,
+            import_settings,
             save_and_close,
             process_text_block,
-            // process_file,
+            detect_line_endings,
+            convert_line_endings,
+            open_text_file,
+            save_text_file,
+            close_text_file,
+            get_supported_encodings,
+            split_file,
+            filter_file,
+            remove_lines_file,
+            sort_file,
             connect_texts,
             get_text_diff,
+            clean_terminal_output,
+            inspect_characters,
+            normalize_text,
+            convert_format,
+            analyze_columns,
+            analyze_text,
+            parse_logs,
+            transform_text,
+            slice_lines,
+            process_timestamps,
+            wrap_text,
+            reflow_text,
+            add_line_prefix,
+            remove_line_prefix,
+            convert_tabs,
+            hash_text,
+            project_columns,
+            transpose_columns,
+            pretty_print_json,
+            minify_json,
+            validate_json,
+            extract_json,
             filter_text,
             remove_lines,
+            sort_text,
+            run_recipe,
+            save_recipe,
+            delete_recipe,
+            export_recipes,
+            import_recipes,
+            cancel_operation,
+            get_metrics_history,
+            clear_metrics_history,
+            export_metrics_csv,
+            run_benchmark,
             is_backend_available,
-            start_memory_measurement,
-            get_memory_diff,
             close_splashscreen,
             set_window_shadow,
         ])
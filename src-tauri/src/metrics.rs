@@ -0,0 +1,212 @@
+use chrono::Local;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Instant;
+use thousands::Separable;
+
+/// Wie viele Messungen pro Command in der Historie gehalten werden.
+const HISTORY_SIZE: usize = 200;
+
+lazy_static! {
+    static ref HISTORY: Mutex<HashMap<&'static str, VecDeque<BackendMetrics>>> = Mutex::new(HashMap::new());
+}
+
+/// Global Allocator, der pro Thread mitzählt, wie viele Bytes gerade belegt sind und wie hoch der Peak war.
+/// Pro Thread, damit parallel laufende Commands sich nicht gegenseitig in die Messung schreiben.
+pub struct TrackingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+    static HISTORY_PAUSED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record(delta: isize) {
+    // `try_with`, weil während des Thread-Abbaus keine TLS mehr verfügbar ist
+    let _ = ALLOCATED.try_with(|allocated| {
+        let current = allocated.get() + delta;
+        allocated.set(current);
+        let _ = PEAK.try_with(|peak| {
+            if current > peak.get() {
+                peak.set(current);
+            }
+        });
+    });
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as isize));
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PhaseTiming {
+    pub name: &'static str,
+    pub time_ms: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BackendMetrics {
+    pub(crate) command: &'static str,
+    pub(crate) timestamp: String,
+    pub(crate) execution_time_ms: f64,
+    /// Peak der zusätzlich belegten Bytes während der Operation, in KB
+    pub(crate) memory_usage_kb: usize,
+    pub(crate) memory_usage_str: String,
+    pub(crate) peak_alloc_bytes: usize,
+    pub(crate) net_alloc_bytes: i64,
+    pub(crate) phases: Vec<PhaseTiming>,
+    pub(crate) input_bytes: usize,
+    pub(crate) output_bytes: usize,
+}
+
+/// Misst eine einzelne Operation: Zeit pro Phase (parse, process, serialize) und den Speicher,
+/// den der aktuelle Thread seit `start` zusätzlich belegt hat.
+pub struct MetricsCollector {
+    command: &'static str,
+    input_bytes: usize,
+    baseline: isize,
+    start_time: Instant,
+    phase_start: Instant,
+    phases: Vec<PhaseTiming>,
+}
+
+impl MetricsCollector {
+    pub fn start(command: &'static str, input_bytes: usize) -> Self {
+        let baseline = ALLOCATED.with(Cell::get);
+        PEAK.with(|peak| peak.set(baseline));
+        let now = Instant::now();
+
+        Self {
+            command,
+            input_bytes,
+            baseline,
+            start_time: now,
+            phase_start: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Schließt die laufende Phase unter dem angegebenen Namen ab.
+    pub fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.phases.push(PhaseTiming {
+            name,
+            time_ms: (now - self.phase_start).as_secs_f64() * 1000.0,
+        });
+        self.phase_start = now;
+    }
+
+    pub fn finish(self, output_bytes: usize) -> BackendMetrics {
+        let execution_time_ms = self.start_time.elapsed().as_secs_f64() * 1000.0;
+        let peak_alloc_bytes = (PEAK.with(Cell::get) - self.baseline).max(0) as usize;
+        let net_alloc_bytes = (ALLOCATED.with(Cell::get) - self.baseline) as i64;
+
+        let metrics = BackendMetrics {
+            command: self.command,
+            timestamp: Local::now().to_rfc3339(),
+            execution_time_ms,
+            memory_usage_kb: peak_alloc_bytes / 1024,
+            memory_usage_str: peak_alloc_bytes.separate_with_commas(),
+            peak_alloc_bytes,
+            net_alloc_bytes,
+            phases: self.phases,
+            input_bytes: self.input_bytes,
+            output_bytes,
+        };
+
+        if !HISTORY_PAUSED.with(Cell::get) {
+            push_history(metrics.clone());
+        }
+        metrics
+    }
+}
+
+fn push_history(metrics: BackendMetrics) {
+    let mut history = HISTORY.lock().unwrap();
+    let entries = history.entry(metrics.command).or_default();
+    if entries.len() >= HISTORY_SIZE {
+        entries.pop_front();
+    }
+    entries.push_back(metrics);
+}
+
+/// Solange der Guard lebt, landen Messungen dieses Threads nicht in der Historie (z.B. bei Benchmarks).
+pub struct HistoryPause(bool);
+
+pub fn pause_history() -> HistoryPause {
+    HistoryPause(HISTORY_PAUSED.with(|paused| paused.replace(true)))
+}
+
+impl Drop for HistoryPause {
+    fn drop(&mut self) {
+        HISTORY_PAUSED.with(|paused| paused.set(self.0));
+    }
+}
+
+/// Historie eines Commands (oder aller Commands), älteste Messung zuerst.
+pub fn get_history(command: Option<&str>) -> Vec<BackendMetrics> {
+    let history = HISTORY.lock().unwrap();
+    let mut entries: Vec<BackendMetrics> = history
+        .iter()
+        .filter(|(name, _)| command.is_none_or(|c| c == **name))
+        .flat_map(|(_, entries)| entries.iter().cloned())
+        .collect();
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries
+}
+
+pub fn clear_history() {
+    HISTORY.lock().unwrap().clear();
+}
+
+pub fn history_to_csv(entries: &[BackendMetrics]) -> String {
+    let mut csv = String::from(
+        "timestamp,command,execution_time_ms,peak_alloc_bytes,net_alloc_bytes,input_bytes,output_bytes,phases\n",
+    );
+    for m in entries {
+        let phases = m
+            .phases
+            .iter()
+            .map(|p| format!("{}={:.3}", p.name, p.time_ms))
+            .collect::<Vec<_>>()
+            .join(";");
+        let _ = writeln!(
+            csv,
+            "{},{},{:.3},{},{},{},{},{}",
+            m.timestamp, m.command, m.execution_time_ms, m.peak_alloc_bytes, m.net_alloc_bytes, m.input_bytes, m.output_bytes, phases
+        );
+    }
+    csv
+}
@@ -0,0 +1,227 @@
+use lazy_static::lazy_static;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// Die Uhr wird nur alle N Zeilen abgefragt, der Abbruch-Check passiert bei jeder Zeile.
+const PROGRESS_STRIDE: usize = 1024;
+
+lazy_static! {
+    static ref OPERATIONS: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Error)]
+pub enum OperationError {
+    #[error("Operation wurde abgebrochen")]
+    Cancelled,
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<String> for OperationError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// Wird als `{ kind, message }` ans Frontend geschickt, damit ein Abbruch von einem Fehler unterscheidbar ist.
+impl Serialize for OperationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let kind = match self {
+            Self::Cancelled => "cancelled",
+            Self::Failed(_) => "failed",
+        };
+        let mut state = serializer.serialize_struct("OperationError", 2)?;
+        state.serialize_field("kind", kind)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationProgress {
+    pub operation_id: String,
+    pub operation: &'static str,
+    pub lines_processed: usize,
+    pub total_lines: Option<usize>,
+    pub bytes_processed: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub percent: f64,
+}
+
+type ProgressCallback = Box<dyn Fn(&OperationProgress) + Send>;
+
+/// Laufende Operation mit Abbruch-Token. Ist eine ID gesetzt, wird sie registriert,
+/// sodass `cancel_operation(id)` sie stoppen kann; beim Drop wird sie wieder entfernt.
+pub struct Operation {
+    id: Option<String>,
+    name: &'static str,
+    cancelled: Arc<AtomicBool>,
+    last_report: Cell<Instant>,
+    /// Zuletzt gemeldete Gesamtzahl der Zeilen, für das abschließende Event
+    total_lines: Cell<Option<usize>>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl Operation {
+    pub fn start(id: Option<String>, name: &'static str, on_progress: Option<ProgressCallback>) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Some(id) = &id {
+            OPERATIONS.lock().unwrap().insert(id.clone(), cancelled.clone());
+        }
+
+        Self {
+            id,
+            name,
+            cancelled,
+            last_report: Cell::new(Instant::now()),
+            total_lines: Cell::new(None),
+            on_progress,
+        }
+    }
+
+    /// Für interne Aufrufe (Recipes, Benchmarks), die weder Fortschritt melden noch abbrechbar sind.
+    pub fn untracked() -> Self {
+        Self::start(None, "", None)
+    }
+
+    pub fn check(&self) -> Result<(), OperationError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(OperationError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fortschritt für Operationen auf Text im Speicher.
+    pub fn progress(&self, lines_processed: usize, total_lines: usize) -> Result<(), OperationError> {
+        self.check()?;
+        self.total_lines.set(Some(total_lines));
+        if lines_processed.is_multiple_of(PROGRESS_STRIDE) && self.due() {
+            let percent = if total_lines == 0 {
+                100.0
+            } else {
+                lines_processed as f64 / total_lines as f64 * 100.0
+            };
+            self.emit(lines_processed, Some(total_lines), None, None, percent);
+        }
+        Ok(())
+    }
+
+    /// Fortschritt für gestreamte Dateien, bei denen nur die Bytes vorab bekannt sind.
+    pub fn progress_bytes(
+        &self,
+        lines_processed: usize,
+        bytes_processed: u64,
+        total_bytes: u64,
+        force: bool,
+    ) -> Result<(), OperationError> {
+        self.check()?;
+        if force || (lines_processed.is_multiple_of(PROGRESS_STRIDE) && self.due()) {
+            let percent = if total_bytes == 0 {
+                100.0
+            } else {
+                bytes_processed as f64 / total_bytes as f64 * 100.0
+            };
+            self.emit(lines_processed, None, Some(bytes_processed), Some(total_bytes), percent);
+        }
+        Ok(())
+    }
+
+    /// Meldet 100 %, damit das Frontend auch bei Text im Speicher ein abschließendes Event bekommt
+    /// (Dateioperationen melden das bereits über `progress_bytes(.., true)`).
+    pub fn complete(&self) {
+        let total_lines = self.total_lines.get();
+        self.emit(total_lines.unwrap_or(0), total_lines, None, None, 100.0);
+    }
+
+    fn due(&self) -> bool {
+        self.last_report.get().elapsed() >= PROGRESS_INTERVAL
+    }
+
+    fn emit(
+        &self,
+        lines_processed: usize,
+        total_lines: Option<usize>,
+        bytes_processed: Option<u64>,
+        total_bytes: Option<u64>,
+        percent: f64,
+    ) {
+        self.last_report.set(Instant::now());
+        if let (Some(id), Some(on_progress)) = (&self.id, &self.on_progress) {
+            on_progress(&OperationProgress {
+                operation_id: id.clone(),
+                operation: self.name,
+                lines_processed,
+                total_lines,
+                bytes_processed,
+                total_bytes,
+                percent,
+            });
+        }
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        // Wurde die ID inzwischen von einer neueren Operation übernommen, deren Eintrag stehen lassen
+        if let Some(id) = &self.id {
+            let mut operations = OPERATIONS.lock().unwrap();
+            if operations.get(id).is_some_and(|token| Arc::ptr_eq(token, &self.cancelled)) {
+                operations.remove(id);
+            }
+        }
+    }
+}
+
+/// Setzt das Abbruch-Token der Operation. Gibt `false` zurück, wenn keine Operation mit der ID läuft.
+pub fn cancel_operation(id: &str) -> bool {
+    match OPERATIONS.lock().unwrap().get(id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_before_task_starts() {
+        fn assert_send<T: Send>(_: &T) {}
+        let op = Operation::start(Some("early-cancel".to_string()), "test", None);
+        assert_send(&op);
+        assert!(cancel_operation("early-cancel"));
+
+        let handle = std::thread::spawn(move || op.check().is_err());
+        assert!(handle.join().unwrap());
+        assert!(!cancel_operation("early-cancel"));
+    }
+
+    #[test]
+    fn complete_reports_full_progress() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let op = Operation::start(
+            Some("complete".to_string()),
+            "test",
+            Some(Box::new(move |progress: &OperationProgress| sink.lock().unwrap().push(progress.clone()))),
+        );
+        op.progress(1, 3).unwrap();
+        op.complete();
+
+        let events = events.lock().unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last.percent, 100.0);
+        assert_eq!((last.lines_processed, last.total_lines), (3, Some(3)));
+    }
+}
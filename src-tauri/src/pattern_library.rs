@@ -0,0 +1,171 @@
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Gespeichertes Suchmuster mit Beschreibung, Tags und Beispielen, damit klar bleibt wofür es gedacht ist.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", from = "SavedPatternRepr")]
+pub struct SavedPattern {
+    pub name: String,
+    pub pattern: String,
+    pub flags: PatternFlags,
+    pub tags: Vec<String>,
+    pub description: String,
+    pub examples: Vec<PatternExample>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PatternFlags {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub multiline: bool,
+}
+
+/// Beispiel-Eingabe mit den Treffern, die das Muster darauf liefern muss.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternExample {
+    pub input: String,
+    #[serde(default)]
+    pub expected_matches: Vec<String>,
+}
+
+/// Ältere Settings speichern Muster als reine Strings, die werden als Regex ohne Metadaten übernommen.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedPatternRepr {
+    Plain(String),
+    #[serde(rename_all = "camelCase")]
+    Full {
+        #[serde(default)]
+        name: String,
+        pattern: String,
+        #[serde(default)]
+        flags: PatternFlags,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        examples: Vec<PatternExample>,
+    },
+}
+
+impl From<SavedPatternRepr> for SavedPattern {
+    fn from(repr: SavedPatternRepr) -> Self {
+        match repr {
+            SavedPatternRepr::Plain(pattern) => SavedPattern::from_plain(&pattern),
+            SavedPatternRepr::Full { name, pattern, flags, tags, description, examples } => SavedPattern {
+                name: if name.is_empty() { pattern.clone() } else { name },
+                pattern,
+                flags,
+                tags,
+                description,
+                examples,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExampleResult {
+    pub input: String,
+    pub expected_matches: Vec<String>,
+    pub actual_matches: Vec<String>,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PatternValidation {
+    pub name: String,
+    pub valid: bool,
+    pub error: Option<String>,
+    pub examples: Vec<ExampleResult>,
+}
+
+impl SavedPattern {
+    pub fn from_plain(pattern: &str) -> Self {
+        SavedPattern {
+            name: pattern.to_string(),
+            pattern: pattern.to_string(),
+            flags: PatternFlags {
+                regex: true,
+                case_sensitive: true,
+                multiline: false,
+            },
+            tags: vec![],
+            description: String::new(),
+            examples: vec![],
+        }
+    }
+
+    /// Baut die Regex mit den gespeicherten Flags; reine Textmuster werden escaped.
+    pub fn compile(&self) -> Result<Regex, String> {
+        let body = if self.flags.regex {
+            self.pattern.clone()
+        } else {
+            fancy_regex::escape(&self.pattern).into_owned()
+        };
+
+        let mut flags = String::new();
+        if !self.flags.case_sensitive {
+            flags.push('i');
+        }
+        if self.flags.multiline {
+            flags.push('m');
+        }
+
+        let full = if flags.is_empty() { body } else { format!("(?{}){}", flags, body) };
+        Regex::new(&full).map_err(|e| format!("⚠ Ungültiges Regex-Pattern: {}", e))
+    }
+
+    /// Kompiliert das Muster und prüft alle Beispiele. Die Treffer müssen in Reihenfolge übereinstimmen.
+    pub fn validate(&self) -> PatternValidation {
+        let regex = match self.compile() {
+            Ok(regex) => regex,
+            Err(e) => {
+                return PatternValidation {
+                    name: self.name.clone(),
+                    valid: false,
+                    error: Some(e),
+                    examples: vec![],
+                }
+            }
+        };
+
+        let mut error = None;
+        let examples: Vec<ExampleResult> = self
+            .examples
+            .iter()
+            .map(|example| {
+                let mut actual_matches = Vec::new();
+                for m in regex.find_iter(&example.input) {
+                    match m {
+                        Ok(m) => actual_matches.push(m.as_str().to_string()),
+                        Err(e) => {
+                            error.get_or_insert_with(|| format!("⚠ Fehler beim Ausführen: {}", e));
+                            break;
+                        }
+                    }
+                }
+                ExampleResult {
+                    input: example.input.clone(),
+                    expected_matches: example.expected_matches.clone(),
+                    passed: actual_matches == example.expected_matches,
+                    actual_matches,
+                }
+            })
+            .collect();
+
+        PatternValidation {
+            name: self.name.clone(),
+            valid: error.is_none() && examples.iter().all(|e| e.passed),
+            error,
+            examples,
+        }
+    }
+}
+
+pub fn validate_patterns(patterns: &[SavedPattern]) -> Vec<PatternValidation> {
+    patterns.iter().map(SavedPattern::validate).collect()
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::pattern_library::SavedPattern;
+use crate::settings::SaveAppSettings;
+
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// Benanntes Profil (z.B. "Logs" oder "Datenbereinigung") mit eigenen Defaults und Presets pro Seite.
+/// Die Werte des aktiven Profils stehen zusätzlich direkt in `SaveAppSettings`, damit das Frontend sie wie bisher liest.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProfile {
+    pub name: String,
+    pub trim_line: Option<bool>,
+    pub trim_parts: Option<bool>,
+    pub show_empty_lines: Option<bool>,
+    pub saved_patterns: Option<Vec<SavedPattern>>,
+    /// Key ist die Seite, z.B. "split", "filter", "remove", "sort", "diff"
+    #[serde(default)]
+    pub page_presets: HashMap<String, PagePreset>,
+}
+
+/// Optionen einer Seite. Nicht gesetzte Felder behalten im Frontend ihren Standardwert.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PagePreset {
+    pub delimiter: Option<String>,
+    pub pattern: Option<String>,
+    pub filter_mode: Option<String>,
+    pub remove_mode: Option<String>,
+    pub sort_mode: Option<String>,
+    pub case_sensitive: Option<bool>,
+    pub use_regex: Option<bool>,
+    pub split_matches: Option<bool>,
+    pub trim_parts: Option<bool>,
+    pub ignore_whitespace: Option<bool>,
+}
+
+impl SettingsProfile {
+    fn from_settings(name: &str, settings: &SaveAppSettings) -> Self {
+        Self {
+            name: name.to_string(),
+            trim_line: settings.trim_line,
+            trim_parts: settings.trim_parts,
+            show_empty_lines: settings.show_empty_lines,
+            saved_patterns: settings.saved_patterns.clone(),
+            page_presets: HashMap::new(),
+        }
+    }
+}
+
+impl SaveAppSettings {
+    pub fn active_profile_name(&self) -> &str {
+        self.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    fn profile(&self, name: &str) -> Option<&SettingsProfile> {
+        self.profiles.iter().flatten().find(|p| p.name == name)
+    }
+
+    fn profile_mut(&mut self, name: &str) -> Option<&mut SettingsProfile> {
+        self.profiles.iter_mut().flatten().find(|p| p.name == name)
+    }
+
+    /// Schreibt die aktuellen Werte zurück ins aktive Profil (legt es bei Bedarf an).
+    pub fn sync_active_profile(&mut self) {
+        let name = self.active_profile_name().to_string();
+        let snapshot = SettingsProfile::from_settings(&name, self);
+        match self.profile_mut(&name) {
+            Some(profile) => {
+                profile.trim_line = snapshot.trim_line;
+                profile.trim_parts = snapshot.trim_parts;
+                profile.show_empty_lines = snapshot.show_empty_lines;
+                profile.saved_patterns = snapshot.saved_patterns;
+            }
+            None => self.profiles.get_or_insert_with(Vec::new).push(snapshot),
+        }
+        self.active_profile = Some(name);
+    }
+
+    pub fn create_profile(&mut self, name: &str) -> Result<(), String> {
+        self.ensure_new_name(name)?;
+        let defaults = SaveAppSettings::default();
+        self.profiles
+            .get_or_insert_with(Vec::new)
+            .push(SettingsProfile::from_settings(name, &defaults));
+        Ok(())
+    }
+
+    pub fn clone_profile(&mut self, source: &str, name: &str) -> Result<(), String> {
+        self.ensure_new_name(name)?;
+        self.sync_active_profile();
+        let mut profile = self
+            .profile(source)
+            .cloned()
+            .ok_or_else(|| format!("⚠ Profil '{}' nicht gefunden", source))?;
+        profile.name = name.to_string();
+        self.profiles.get_or_insert_with(Vec::new).push(profile);
+        Ok(())
+    }
+
+    /// Sichert das aktive Profil und übernimmt die Werte des Zielprofils in die Settings.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), String> {
+        self.sync_active_profile();
+        self.apply_profile(name)
+    }
+
+    /// Übernimmt die Werte des Profils in die Settings, ohne das bisher aktive vorher zu sichern.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), String> {
+        let profile = self
+            .profile(name)
+            .cloned()
+            .ok_or_else(|| format!("⚠ Profil '{}' nicht gefunden", name))?;
+
+        self.trim_line = profile.trim_line;
+        self.trim_parts = profile.trim_parts;
+        self.show_empty_lines = profile.show_empty_lines;
+        self.saved_patterns = profile.saved_patterns;
+        self.active_profile = Some(profile.name);
+        Ok(())
+    }
+
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), String> {
+        if name == self.active_profile_name() {
+            return Err("⚠ Das aktive Profil kann nicht gelöscht werden".to_string());
+        }
+        let profiles = self.profiles.get_or_insert_with(Vec::new);
+        let before = profiles.len();
+        profiles.retain(|p| p.name != name);
+        if profiles.len() == before {
+            return Err(format!("⚠ Profil '{}' nicht gefunden", name));
+        }
+        Ok(())
+    }
+
+    pub fn page_preset(&self, page: &str) -> Option<PagePreset> {
+        self.profile(self.active_profile_name())
+            .and_then(|p| p.page_presets.get(page).cloned())
+    }
+
+    pub fn set_page_preset(&mut self, page: &str, preset: PagePreset) {
+        self.sync_active_profile();
+        let name = self.active_profile_name().to_string();
+        if let Some(profile) = self.profile_mut(&name) {
+            profile.page_presets.insert(page.to_string(), preset);
+        }
+    }
+
+    fn ensure_new_name(&self, name: &str) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("⚠ Profil braucht einen Namen".to_string());
+        }
+        if self.profile(name).is_some() {
+            return Err(format!("⚠ Profil '{}' existiert bereits", name));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::column_projection;
+use crate::format_conversion::{self, ConversionOptions};
+use crate::hashing::{self, HashOptions};
+use crate::json_tools;
+use crate::line_slicing::{self, SliceOptions};
+use crate::operations::{Operation, OperationError};
+use crate::records::{self, RecordSplit};
+use crate::terminal_cleanup;
+use crate::transforms::{self, TransformDirection, TransformKind};
+use crate::unicode_inspector::{self, NormalizeOptions};
+use crate::text_processor::{ProcessedText, TextProcessor};
+use crate::timestamps::{self, TimestampOptions};
+
+/// Gespeicherte, benannte Verarbeitungskette ("Recipe"), z.B. "clean nginx log".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<RecipeParameter>,
+    pub steps: Vec<RecipeStep>,
+}
+
+/// Parameter, der beim Ausführen des Recipes abgefragt wird.
+/// In den Step-Argumenten wird er als `{{name}}` referenziert.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeParameter {
+    pub name: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeStep {
+    pub operation: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+}
+
+/// Format der exportierten Datei, damit Recipes im Team geteilt werden können.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecipeFile {
+    Many { recipes: Vec<Recipe> },
+    List(Vec<Recipe>),
+    Single(Recipe),
+}
+
+impl Recipe {
+    /// Führt alle Steps nacheinander aus, das Ergebnis eines Steps ist die Eingabe des nächsten.
+    pub fn run(
+        &self,
+        text: &str,
+        params: &HashMap<String, String>,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        let values = self.resolve_parameters(params)?;
+
+        let mut output = text.to_string();
+        let mut execution_time = 0.0;
+        let mut removed_lines = 0;
+        let mut metrics = None;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let args = substitute(&step.args, &values);
+            let result = run_step(&output, &step.operation, &args, op).map_err(|e| match e {
+                OperationError::Cancelled => e,
+                _ => OperationError::Failed(format!("Step {} ({}): {}", index + 1, step.operation, e)),
+            })?;
+
+            execution_time += result.metrics.execution_time_ms;
+            removed_lines += result.removed_lines;
+            metrics = Some(result.metrics);
+            output = result.result_text;
+        }
+
+        let mut metrics = metrics.ok_or_else(|| format!("⚠ Recipe '{}' hat keine Steps", self.name))?;
+        metrics.execution_time_ms = execution_time;
+
+        Ok(ProcessedText {
+            result_text: output,
+            removed_lines,
+            metrics,
+        })
+    }
+
+    fn resolve_parameters(&self, params: &HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+        self.parameters
+            .iter()
+            .map(|p| {
+                params
+                    .get(&p.name)
+                    .or(p.default_value.as_ref())
+                    .map(|v| (p.name.clone(), v.clone()))
+                    .ok_or_else(|| format!("⚠ Parameter '{}' fehlt", p.name))
+            })
+            .collect()
+    }
+}
+
+/// Ersetzt `{{name}}` in allen String-Argumenten durch den Parameterwert.
+fn substitute(args: &Map<String, Value>, values: &HashMap<String, String>) -> Map<String, Value> {
+    args.iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => Value::String(substitute_str(s, values)),
+                other => other.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// Ein Durchlauf von links nach rechts: eingesetzte Werte werden nicht erneut ersetzt,
+/// unbekannte Platzhalter bleiben stehen.
+fn substitute_str(s: &str, values: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}").and_then(|end| values.get(&after[..end]).map(|v| (end, v))) {
+            Some((end, v)) => {
+                result.push_str(v);
+                rest = &after[end + 2..];
+            }
+            None => {
+                result.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+pub(crate) fn arg_str<'a>(args: &'a Map<String, Value>, key: &str) -> &'a str {
+    args.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+pub(crate) fn arg_bool(args: &Map<String, Value>, key: &str) -> bool {
+    match args.get(key) {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::String(s)) => s.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+/// Optionaler Datensatz-Modus (`{"mode": "blankLine"}` usw.) für filter, remove und sort.
+fn arg_records(args: &Map<String, Value>) -> Result<Option<RecordSplit>, String> {
+    match args.get("records") {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("⚠ Ungültiger Datensatz-Modus: {}", e)),
+    }
+}
+
+pub(crate) fn arg_usize(args: &Map<String, Value>, key: &str, default: usize) -> usize {
+    match args.get(key) {
+        Some(Value::Number(n)) => n.as_u64().map(|n| n as usize).unwrap_or(default),
+        Some(Value::String(s)) => s.trim().parse().unwrap_or(default),
+        _ => default,
+    }
+}
+
+pub(crate) fn run_step(
+    text: &str,
+    operation: &str,
+    args: &Map<String, Value>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let records = arg_records(args)?;
+    match operation {
+        "filter" => match &records {
+            Some(split) => records::filter_records(
+                text,
+                split,
+                arg_str(args, "pattern"),
+                arg_str(args, "filtermode"),
+                arg_bool(args, "casesensitive"),
+                op,
+            ),
+            None => TextProcessor::filter_text_internal(
+                text,
+                arg_str(args, "pattern"),
+                arg_str(args, "filtermode"),
+                arg_bool(args, "casesensitive"),
+                arg_bool(args, "splitmatches"),
+                op,
+            ),
+        },
+        "remove" => match &records {
+            Some(split) => records::remove_records(
+                text,
+                split,
+                arg_str(args, "pattern"),
+                arg_str(args, "removemode"),
+                arg_bool(args, "casesensitive"),
+                arg_bool(args, "useregex"),
+                arg_bool(args, "trimparts"),
+                None,
+                op,
+            ),
+            None => TextProcessor::remove_lines_internal(
+                text,
+                arg_str(args, "pattern"),
+                arg_str(args, "removemode"),
+                arg_bool(args, "casesensitive"),
+                arg_bool(args, "useregex"),
+                arg_bool(args, "trimparts"),
+                None,
+                op,
+            ),
+        },
+        "sort" => match &records {
+            Some(split) => records::sort_records(
+                text,
+                split,
+                arg_str(args, "sortmode"),
+                arg_bool(args, "casesensitive"),
+                op,
+            ),
+            None => TextProcessor::sort_lines_internal(
+                text,
+                arg_str(args, "sortmode"),
+                arg_bool(args, "casesensitive"),
+                op,
+            ),
+        },
+        "wrap" => TextProcessor::wrap_lines(text, arg_usize(args, "width", 80), arg_bool(args, "breakwords"), op),
+        "reflow" => TextProcessor::reflow_paragraphs(text, arg_usize(args, "width", 0), op),
+        "addPrefix" => TextProcessor::add_line_prefix(text, arg_str(args, "prefix"), arg_bool(args, "skipempty"), op),
+        "removePrefix" => TextProcessor::remove_line_prefix(text, arg_str(args, "prefix"), op),
+        "convertTabs" => TextProcessor::convert_tabs(text, arg_usize(args, "tabstop", 4), arg_bool(args, "totabs"), op),
+        "cleanTerminal" => terminal_cleanup::clean_terminal_output(text, arg_bool(args, "removeprogress"), op)
+            .map(|cleaned| cleaned.map(|result| result.text)),
+        "normalize" => {
+            let options: NormalizeOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für normalize: {}", e))?;
+            unicode_inspector::normalize_text(text, &options, op)
+        }
+        "jsonPretty" => json_tools::pretty_print(
+            text,
+            arg_usize(args, "indent", 2),
+            arg_bool(args, "sortkeys"),
+            None,
+            op,
+        ),
+        "jsonMinify" => json_tools::minify(text, arg_bool(args, "sortkeys"), None, op),
+        "jsonExtract" => json_tools::extract(text, arg_str(args, "path"), arg_bool(args, "raw"), None, op),
+        "convert" => {
+            let options: ConversionOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für convert: {}", e))?;
+            format_conversion::convert_format(text, &options, op)
+        }
+        "cut" | "transpose" => {
+            let options: ConversionOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für {}: {}", operation, e))?;
+            if operation == "cut" {
+                column_projection::project_columns(text, arg_str(args, "columns"), arg_bool(args, "drop"), &options, op)
+            } else {
+                column_projection::transpose(text, &options, op)
+            }
+        }
+        "encode" | "decode" => {
+            let kind: TransformKind = serde_json::from_value(args.get("kind").cloned().unwrap_or_default())
+                .map_err(|e| format!("⚠ Ungültige Kodierung für {}: {}", operation, e))?;
+            let direction = if operation == "encode" { TransformDirection::Encode } else { TransformDirection::Decode };
+            let result = transforms::transform_text(text, kind, direction, arg_bool(args, "perline"), op)?;
+            // Im Recipe würde ein teilweise dekodierter Text unbemerkt weiterverarbeitet
+            if let Some(first) = result.result_text.errors.first() {
+                return Err(format!(
+                    "⚠ {} Fehler beim Dekodieren, zuerst Zeile {}: {}",
+                    result.result_text.errors.len(),
+                    first.line,
+                    first.message
+                )
+                .into());
+            }
+            Ok(result.map(|result| result.text))
+        }
+        "hash" => {
+            let options: HashOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für hash: {}", e))?;
+            hashing::hash_text(text, &options, op)
+        }
+        "slice" => {
+            let options: SliceOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für slice: {}", e))?;
+            line_slicing::slice_lines(text, &options, op)
+        }
+        "timestamps" => {
+            let options: TimestampOptions = serde_json::from_value(Value::Object(args.clone()))
+                .map_err(|e| format!("⚠ Ungültige Optionen für timestamps: {}", e))?;
+            timestamps::process_timestamps(text, &options, op)
+        }
+        _ => Err(format!("⚠ Unbekannte Operation '{}'", operation).into()),
+    }
+}
+
+/// Fügt Recipes in die bestehende Liste ein, gleichnamige werden ersetzt.
+pub fn merge_recipes(existing: &mut Vec<Recipe>, incoming: Vec<Recipe>) -> Result<Vec<String>, String> {
+    let mut merged = existing.clone();
+    let mut names = Vec::new();
+    for recipe in incoming {
+        validate_recipe(&merged, &recipe)?;
+        names.push(recipe.name.clone());
+        match merged.iter_mut().find(|r| r.name == recipe.name) {
+            Some(slot) => *slot = recipe,
+            None => merged.push(recipe),
+        }
+    }
+    *existing = merged;
+    Ok(names)
+}
+
+fn validate_recipe(existing: &[Recipe], recipe: &Recipe) -> Result<(), String> {
+    if recipe.name.trim().is_empty() {
+        return Err("⚠ Recipe braucht einen Namen".to_string());
+    }
+    if let Some(hotkey) = &recipe.hotkey {
+        if let Some(other) = existing
+            .iter()
+            .find(|r| r.name != recipe.name && r.hotkey.as_deref() == Some(hotkey.as_str()))
+        {
+            return Err(format!("⚠ Hotkey '{}' wird bereits von '{}' verwendet", hotkey, other.name));
+        }
+    }
+    Ok(())
+}
+
+pub fn export_recipes(recipes: &[Recipe], file_path: &str) -> Result<(), String> {
+    let file = RecipeFile::Many { recipes: recipes.to_vec() };
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fs::write(file_path, json).map_err(|e| format!("Fehler beim Exportieren der Recipes: {:?}", e))
+}
+
+pub fn import_recipes(file_path: &str) -> Result<Vec<Recipe>, String> {
+    let data = fs::read_to_string(file_path).map_err(|e| format!("Fehler beim Lesen der Datei: {:?}", e))?;
+    let file: RecipeFile = serde_json::from_str(&data).map_err(|e| format!("⚠ Ungültige Recipe-Datei: {}", e))?;
+    Ok(match file {
+        RecipeFile::Many { recipes } | RecipeFile::List(recipes) => recipes,
+        RecipeFile::Single(recipe) => vec![recipe],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_does_not_expand_inserted_values() {
+        let values = HashMap::from([
+            ("a".to_string(), "{{b}}".to_string()),
+            ("b".to_string(), "x".to_string()),
+        ]);
+        assert_eq!(substitute_str("{{a}}-{{b}}-{{c}}", &values), "{{b}}-x-{{c}}");
+    }
+}
@@ -0,0 +1,229 @@
+use fancy_regex::Regex;
+use serde::Deserialize;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{LineRemover, ProcessedText, SortMode, TextProcessor};
+use crate::unicode_inspector::NormalizeOptions;
+
+/// Wie mehrzeilige Datensätze (Stacktraces, LDIF-Einträge, Log-Records) abgegrenzt werden.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum RecordSplit {
+    /// Leerzeilen trennen die Datensätze
+    BlankLine,
+    /// Eine Zeile, die (ohne Leerzeichen am Rand) genau `separator` ist, z.B. `---` oder `%%`
+    Separator { separator: String },
+    /// Jede Zeile, auf die `pattern` passt, beginnt einen neuen Datensatz (z.B. ein Zeitstempel)
+    StartsWith { pattern: String, casesensitive: Option<bool> },
+}
+
+struct Records<'a> {
+    records: Vec<Vec<&'a str>>,
+    /// Wird beim Zusammensetzen zwischen die Datensätze gesetzt
+    joiner: String,
+}
+
+impl RecordSplit {
+    fn split<'a>(&self, text: &'a str, op: &Operation) -> Result<Records<'a>, OperationError> {
+        let start_regex = match self {
+            RecordSplit::StartsWith { pattern, casesensitive } => {
+                Some(TextProcessor::build_filter_regex(pattern, casesensitive.unwrap_or(true))?)
+            }
+            _ => None,
+        };
+        let is_separator = |line: &str| match self {
+            RecordSplit::BlankLine => line.trim().is_empty(),
+            RecordSplit::Separator { separator } => line.trim() == separator.trim(),
+            RecordSplit::StartsWith { .. } => false,
+        };
+        let starts_record = |line: &str| start_regex.as_ref().is_some_and(|regex: &Regex| regex.is_match(line).unwrap_or(false));
+
+        let lines: Vec<&str> = text.lines().collect();
+        let mut records: Vec<Vec<&str>> = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        for (index, &line) in lines.iter().enumerate() {
+            op.progress(index, lines.len())?;
+            if is_separator(line) {
+                if !current.is_empty() {
+                    records.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            if starts_record(line) && !current.is_empty() {
+                records.push(std::mem::take(&mut current));
+            }
+            current.push(line);
+        }
+        if !current.is_empty() {
+            records.push(current);
+        }
+
+        let joiner = match self {
+            RecordSplit::BlankLine => "\n\n".to_string(),
+            RecordSplit::Separator { separator } => format!("\n{}\n", separator.trim()),
+            RecordSplit::StartsWith { .. } => "\n".to_string(),
+        };
+        Ok(Records { records, joiner })
+    }
+}
+
+fn finish(
+    mut collector: MetricsCollector,
+    kept: Vec<String>,
+    joiner: &str,
+    removed_lines: usize,
+) -> ProcessedText<String> {
+    collector.phase("process");
+    let result_text = kept.join(joiner);
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    ProcessedText {
+        result_text,
+        removed_lines,
+        metrics,
+    }
+}
+
+/// Behält alle Datensätze, in denen `pattern` vorkommt; mit `filtermode == "remove"` werden genau diese entfernt.
+pub fn filter_records(
+    text: &str,
+    split: &RecordSplit,
+    pattern: &str,
+    filter_mode: &str,
+    casesensitive: bool,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("filter_records", text.len());
+
+    let regex = TextProcessor::build_filter_regex(pattern, casesensitive)?;
+    let Records { records, joiner } = split.split(text, op)?;
+    collector.phase("parse");
+
+    let keep_matching = filter_mode != "remove";
+    let mut removed_lines = 0;
+    let mut kept = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        op.progress(index, records.len())?;
+        let matched = record.iter().any(|line| regex.is_match(line).unwrap_or(false));
+        if matched == keep_matching {
+            kept.push(record.join("\n"));
+        } else {
+            removed_lines += record.len();
+        }
+    }
+
+    Ok(finish(collector, kept, &joiner, removed_lines))
+}
+
+/// Wie `remove_lines_internal`, aber für ganze Datensätze: ein Treffer in einer Zeile entfernt
+/// (bzw. behält) den ganzen Datensatz, `duplicates` vergleicht komplette Datensätze.
+#[allow(clippy::too_many_arguments)]
+pub fn remove_records(
+    text: &str,
+    split: &RecordSplit,
+    pattern: &str,
+    removemode: &str,
+    casesensitive: bool,
+    useregex: bool,
+    trimparts: bool,
+    normalize: Option<&NormalizeOptions>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("remove_records", text.len());
+
+    let mut remover =
+        LineRemover::new(pattern, removemode, casesensitive, useregex, trimparts)?.with_normalization(normalize)?;
+    let Records { records, joiner } = split.split(text, op)?;
+    collector.phase("parse");
+
+    let mut removed_lines = 0;
+    let mut kept = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        op.progress(index, records.len())?;
+        match remover.apply_record(record) {
+            Some(record_text) => kept.push(record_text),
+            None => removed_lines += record.len(),
+        }
+    }
+
+    Ok(finish(collector, kept, &joiner, removed_lines))
+}
+
+/// Sortiert ganze Datensätze; verglichen wird der komplette Datensatz (also zuerst seine erste Zeile).
+pub fn sort_records(
+    text: &str,
+    split: &RecordSplit,
+    sortmode: &str,
+    casesensitive: bool,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("sort_records", text.len());
+
+    let mode = SortMode::parse(sortmode)?;
+    let Records { records, joiner } = split.split(text, op)?;
+    let mut joined: Vec<String> = records.iter().map(|record| record.join("\n")).collect();
+    collector.phase("parse");
+
+    op.check()?;
+    joined.sort_by(|a, b| mode.compare(a, b, casesensitive));
+    op.check()?;
+
+    Ok(finish(collector, joined, &joiner, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "head A\nERROR boom\n\nhead B\nok\n\nhead A\nERROR boom";
+
+    #[test]
+    fn split_modes() {
+        let op = Operation::untracked();
+        let blank = RecordSplit::BlankLine.split(LOG, &op).unwrap();
+        assert_eq!(blank.records.len(), 3);
+        assert_eq!(blank.records[1], vec!["head B", "ok"]);
+
+        let separator = RecordSplit::Separator { separator: "---".to_string() };
+        let records = separator.split("a\n---\nb\nc\n --- \n", &op).unwrap();
+        assert_eq!(records.records, vec![vec!["a"], vec!["b", "c"]]);
+        assert_eq!(records.joiner, "\n---\n");
+
+        let starts = RecordSplit::StartsWith { pattern: "^\\d".to_string(), casesensitive: None };
+        let records = starts.split("1 a\n  at x\n2 b", &op).unwrap();
+        assert_eq!(records.records, vec![vec!["1 a", "  at x"], vec!["2 b"]]);
+    }
+
+    #[test]
+    fn filter_keeps_or_removes_whole_records() {
+        let op = Operation::untracked();
+        let kept = filter_records(LOG, &RecordSplit::BlankLine, "ERROR", "keep", true, &op).unwrap();
+        assert_eq!(kept.result_text, "head A\nERROR boom\n\nhead A\nERROR boom");
+        assert_eq!(kept.removed_lines, 2);
+
+        let removed = filter_records(LOG, &RecordSplit::BlankLine, "ERROR", "remove", true, &op).unwrap();
+        assert_eq!(removed.result_text, "head B\nok");
+    }
+
+    #[test]
+    fn remove_matches_anchors_on_every_line() {
+        let op = Operation::untracked();
+        let text = "head A\nERROR boom\n\nhead B\nok";
+        let result =
+            remove_records(text, &RecordSplit::BlankLine, "^ERROR", "containing", true, true, false, None, &op).unwrap();
+        assert_eq!(result.result_text, "head B\nok");
+
+        let result = remove_records(text, &RecordSplit::BlankLine, "B$", "containing", true, true, false, None, &op).unwrap();
+        assert_eq!(result.result_text, "head A\nERROR boom");
+    }
+
+    #[test]
+    fn remove_duplicate_records() {
+        let op = Operation::untracked();
+        let result = remove_records(LOG, &RecordSplit::BlankLine, "", "duplicates", true, false, false, None, &op).unwrap();
+        assert_eq!(result.result_text, "head A\nERROR boom\n\nhead B\nok");
+        assert_eq!(result.removed_lines, 2);
+    }
+}
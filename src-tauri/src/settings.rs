@@ -1,11 +1,33 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
 use std::fs;
+use std::io::Write;
 
+use crate::pattern_library::SavedPattern;
+use crate::profiles::{SettingsProfile, DEFAULT_PROFILE};
+use crate::recipes::Recipe;
+
+/// Aktuelle Version des Settings-Formats. Bei Änderungen am Format hochzählen und in `migrate` einen Schritt ergänzen.
+pub const SETTINGS_VERSION: u32 = 4;
+
+/// Was beim Laden der Settings passiert ist, damit das Frontend den Nutzer informieren kann.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsLoadReport {
+    pub migrated_from: Option<u32>,
+    pub corrupt_file: Option<String>,
+    pub restored_from_backup: bool,
+    /// Version einer neueren App; die Datei wird dann nicht überschrieben
+    pub newer_version: Option<u32>,
+    pub error: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")] // Erzwingt camelCase für JSON-Keys
 pub struct SaveAppSettings {
+    #[serde(default)]
+    pub schema_version: u32,
     pub is_dark_mode: Option<bool>,
     pub language: Option<String>,
     pub trim_line: Option<bool>,
@@ -13,14 +35,18 @@ pub struct SaveAppSettings {
     pub font_size: Option<String>,
     pub show_line_numbers: Option<bool>,
     pub show_empty_lines: Option<bool>,
-    pub saved_patterns: Option<Vec<String>>,
+    pub saved_patterns: Option<Vec<SavedPattern>>,
+    pub recipes: Option<Vec<Recipe>>,
+    pub profiles: Option<Vec<SettingsProfile>>,
+    pub active_profile: Option<String>,
     pub window_position: Option<(i32, i32)>,
     pub window_size: Option<(u32, u32)>,
 }
 
 impl Default for SaveAppSettings {
     fn default() -> Self {
-        Self {
+        let mut settings = Self {
+            schema_version: SETTINGS_VERSION,
             is_dark_mode: Some(true),
             language: Some("en".to_string()),
             trim_line: Some(false),
@@ -29,9 +55,14 @@ impl Default for SaveAppSettings {
             show_line_numbers: Some(false),
             show_empty_lines: Some(false),
             saved_patterns: Some(vec![]),
+            recipes: Some(vec![]),
+            profiles: Some(vec![]),
+            active_profile: Some(DEFAULT_PROFILE.to_string()),
             window_position: Some((100, 100)),
             window_size: Some((1200, 800)),
-        }
+        };
+        settings.sync_active_profile();
+        settings
     }
 }
 
@@ -48,22 +79,276 @@ impl SaveAppSettings {
     }
 
 
+    /// Das Frontend schickt bei jeder Änderung seinen kompletten, evtl. veralteten Stand zurück.
+    /// Recipes, Profile, aktives Profil und Fensterposition verwaltet nur das Backend, sie kommen
+    /// deshalb immer aus `previous`. Gehört der Stand noch zu einem anderen Profil (z.B. nach
+    /// `switch_profile`), werden auch die profilbezogenen Felder nicht übernommen.
+    pub fn keep_backend_fields(&mut self, previous: &mut SaveAppSettings) {
+        self.schema_version = self.schema_version.max(previous.schema_version);
+        if self.active_profile.is_some() && self.active_profile != previous.active_profile {
+            self.trim_line = previous.trim_line;
+            self.trim_parts = previous.trim_parts;
+            self.show_empty_lines = previous.show_empty_lines;
+            self.saved_patterns = previous.saved_patterns.take();
+        }
+        self.recipes = previous.recipes.take();
+        self.profiles = previous.profiles.take();
+        self.active_profile = previous.active_profile.take();
+        self.window_position = previous.window_position;
+        self.window_size = previous.window_size;
+    }
+
+    fn get_backup_path(path: &Path) -> PathBuf {
+        path.with_extension("json.bak")
+    }
+
     // Settings aus Datei laden
-    pub fn load() -> Self {
-        let path = Self::get_path();
-        if let Ok(data) = fs::read_to_string(&path) {
-            if let Ok(settings) = serde_json::from_str(&data) {
-                return settings;
+    pub fn load() -> (Self, SettingsLoadReport) {
+        Self::load_from(&Self::get_path())
+    }
+
+    /// Lädt und migriert die Settings. Eine beschädigte Datei wird nicht verworfen, sondern
+    /// umbenannt aufbewahrt; danach wird das Backup versucht und erst zuletzt auf Defaults zurückgefallen.
+    pub fn load_from(path: &Path) -> (Self, SettingsLoadReport) {
+        let mut report = SettingsLoadReport::default();
+
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (Self::default(), report),
+            Err(e) => {
+                report.error = Some(format!("Fehler beim Lesen der Settings: {:?}", e));
+                return (Self::default(), report);
+            }
+        };
+
+        match Self::parse(&data, &mut report) {
+            Ok(settings) => return (settings, report),
+            Err(e) => {
+                report.error = Some(e);
+                report.corrupt_file = Self::preserve_corrupt(path);
+            }
+        }
+
+        let backup = Self::get_backup_path(path);
+        if let Ok(data) = fs::read_to_string(&backup) {
+            if let Ok(settings) = Self::parse(&data, &mut report) {
+                report.restored_from_backup = true;
+                return (settings, report);
             }
         }
-        Self::default()
+
+        (Self::default(), report)
+    }
+
+    fn parse(data: &str, report: &mut SettingsLoadReport) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(data).map_err(|e| format!("⚠ Settings-Datei ist beschädigt: {}", e))?;
+        let version = value.get("schemaVersion").and_then(Value::as_u64).unwrap_or(1) as u32;
+
+        let value = migrate(value, version)?;
+        if version < SETTINGS_VERSION {
+            report.migrated_from = Some(version);
+        }
+        if version > SETTINGS_VERSION {
+            report.newer_version = Some(version);
+        }
+
+        let mut settings: Self =
+            serde_json::from_value(value).map_err(|e| format!("⚠ Settings-Datei ist ungültig: {}", e))?;
+        // Neuere Versionen behalten ihre Nummer, damit `save_to` die Datei nicht mit weniger Feldern überschreibt
+        settings.schema_version = version.max(SETTINGS_VERSION);
+        Ok(settings)
+    }
+
+    fn preserve_corrupt(path: &Path) -> Option<String> {
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let target = path.with_extension(format!("corrupt-{}.json", stamp));
+        fs::rename(path, &target).ok()?;
+        eprintln!("⚠ Beschädigte Settings gesichert unter {:?}", target);
+        Some(target.to_string_lossy().into_owned())
     }
 
     // Settings speichern
     pub fn save(&self) -> Result<(), String> {
         let path = Self::get_path();
         println!("Speicherpath der Einstellungen: {:?}", path);
-        std::fs::write(&path, serde_json::to_string_pretty(self).map_err(|e| e.to_string())?)
-            .map_err(|e| format!("Fehler beim Speichern der Settings: {:?}", e))
+        self.save_to(&path)
+    }
+
+    /// Schreibt zuerst in eine temporäre Datei und benennt sie dann um, damit ein Absturz
+    /// beim Schreiben nie eine halbe `settings.json` hinterlässt. Die vorherige Version bleibt als `.bak`.
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if self.schema_version > SETTINGS_VERSION {
+            return Err(format!(
+                "⚠ Settings stammen aus einer neueren Version ({}) und werden nicht überschrieben",
+                self.schema_version
+            ));
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Fehler beim Anlegen des Settings-Ordners: {:?}", e))?;
+        }
+
+        let mut settings = self.clone();
+        settings.schema_version = SETTINGS_VERSION;
+        settings.sync_active_profile();
+        let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+
+        let tmp_path = path.with_extension("json.tmp");
+        let write_tmp = || -> std::io::Result<()> {
+            let mut file = fs::File::create(&tmp_path)?;
+            file.write_all(json.as_bytes())?;
+            file.sync_all()
+        };
+        write_tmp().map_err(|e| format!("Fehler beim Speichern der Settings: {:?}", e))?;
+
+        if path.exists() {
+            fs::copy(path, Self::get_backup_path(path))
+                .map_err(|e| format!("Fehler beim Anlegen des Backups: {:?}", e))?;
+        }
+        fs::rename(&tmp_path, path).map_err(|e| format!("Fehler beim Speichern der Settings: {:?}", e))
+    }
+}
+
+/// Hebt ältere Formate Schritt für Schritt auf `SETTINGS_VERSION`.
+fn migrate(mut value: Value, from: u32) -> Result<Value, String> {
+    if from > SETTINGS_VERSION {
+        eprintln!("⚠ Settings stammen aus einer neueren Version ({}), die Datei wird nur gelesen", from);
+        return Ok(value);
+    }
+
+    for version in from..SETTINGS_VERSION {
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| "⚠ Settings-Datei ist kein JSON-Objekt".to_string())?;
+
+        match version {
+            // v1 -> v2: Recipes eingeführt
+            1 => {
+                object.entry("recipes").or_insert_with(|| Value::Array(vec![]));
+            }
+            // v2 -> v3: Profile eingeführt, die bisherigen Werte werden zum Profil "Default"
+            2 => {
+                let mut profile = serde_json::Map::new();
+                profile.insert("name".to_string(), Value::from(DEFAULT_PROFILE));
+                for key in ["trimLine", "trimParts", "showEmptyLines", "savedPatterns"] {
+                    if let Some(v) = object.get(key) {
+                        profile.insert(key.to_string(), v.clone());
+                    }
+                }
+                object.entry("profiles").or_insert_with(|| Value::Array(vec![Value::Object(profile)]));
+                object.entry("activeProfile").or_insert_with(|| Value::from(DEFAULT_PROFILE));
+            }
+            // v3 -> v4: Muster werden von Strings zu Einträgen der Pattern-Library
+            3 => {
+                upgrade_patterns(object.get_mut("savedPatterns"));
+                if let Some(Value::Array(profiles)) = object.get_mut("profiles") {
+                    for profile in profiles.iter_mut() {
+                        upgrade_patterns(profile.get_mut("savedPatterns"));
+                    }
+                }
+            }
+            _ => {}
+        }
+        object.insert("schemaVersion".to_string(), Value::from(version + 1));
+    }
+    Ok(value)
+}
+
+fn upgrade_patterns(patterns: Option<&mut Value>) {
+    if let Some(Value::Array(patterns)) = patterns {
+        for entry in patterns.iter_mut() {
+            if let Value::String(pattern) = entry {
+                *entry = serde_json::to_value(SavedPattern::from_plain(pattern)).unwrap_or(Value::Null);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("textsplitter-settings-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrate_v1_to_current() {
+        let v1 = json!({
+            "isDarkMode": false,
+            "trimLine": true,
+            "savedPatterns": ["\\d+"],
+        });
+        let migrated = migrate(v1, 1).unwrap();
+
+        assert_eq!(migrated["schemaVersion"], json!(SETTINGS_VERSION));
+        assert_eq!(migrated["recipes"], json!([]));
+        assert_eq!(migrated["activeProfile"], json!(DEFAULT_PROFILE));
+        assert_eq!(migrated["savedPatterns"][0]["pattern"], json!("\\d+"));
+        let profile = &migrated["profiles"][0];
+        assert_eq!(profile["name"], json!(DEFAULT_PROFILE));
+        assert_eq!(profile["trimLine"], json!(true));
+        assert_eq!(profile["savedPatterns"][0]["pattern"], json!("\\d+"));
+
+        let settings: SaveAppSettings = serde_json::from_value(migrated).unwrap();
+        assert_eq!(settings.saved_patterns.unwrap()[0].pattern, "\\d+");
+    }
+
+    #[test]
+    fn stale_frontend_state_keeps_backend_fields() {
+        let mut current = SaveAppSettings::default();
+        current.create_profile("Logs").unwrap();
+        current.switch_profile("Logs").unwrap();
+        current.trim_line = Some(true);
+        current.sync_active_profile();
+
+        // Stand des Frontends von vor dem Profilwechsel
+        let mut incoming = SaveAppSettings {
+            trim_line: Some(false),
+            recipes: Some(vec![]),
+            profiles: Some(vec![]),
+            ..Default::default()
+        };
+        incoming.keep_backend_fields(&mut current.clone());
+        incoming.sync_active_profile();
+
+        assert_eq!(incoming.active_profile.as_deref(), Some("Logs"));
+        assert_eq!(incoming.trim_line, Some(true));
+        assert_eq!(incoming.profiles.as_ref().map(Vec::len), Some(2));
+
+        // Änderungen am aktiven Profil kommen dagegen an
+        let mut update = SaveAppSettings {
+            trim_line: Some(false),
+            active_profile: Some("Logs".to_string()),
+            ..Default::default()
+        };
+        update.keep_backend_fields(&mut incoming);
+        update.sync_active_profile();
+        assert_eq!(update.trim_line, Some(false));
+        assert_eq!(update.profiles.unwrap().iter().find(|p| p.name == "Logs").unwrap().trim_line, Some(false));
+    }
+
+    #[test]
+    fn load_falls_back_to_backup() {
+        let dir = temp_dir("backup");
+        let path = dir.join("settings.json");
+        let mut settings = SaveAppSettings {
+            language: Some("de".to_string()),
+            ..Default::default()
+        };
+        settings.save_to(&path).unwrap();
+        settings.language = Some("fr".to_string());
+        settings.save_to(&path).unwrap();
+        fs::write(&path, "{ kaputt").unwrap();
+
+        let (loaded, report) = SaveAppSettings::load_from(&path);
+        assert_eq!(loaded.language.as_deref(), Some("de"));
+        assert!(report.restored_from_backup);
+        assert!(report.error.is_some());
+        assert!(report.corrupt_file.is_some_and(|file| Path::new(&file).exists()));
+        fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::pattern_library::SavedPattern;
+use crate::profiles::SettingsProfile;
+use crate::recipes::Recipe;
+use crate::settings::{SaveAppSettings, SETTINGS_VERSION};
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingsSection {
+    General,
+    Patterns,
+    Recipes,
+    Profiles,
+}
+
+/// Was bei gleichnamigen Einträgen passiert. Für die allgemeinen Einstellungen wirkt `Rename` wie `KeepMine`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    KeepMine,
+    TakeTheirs,
+    Rename,
+}
+
+/// Portables Austauschformat (JSON oder TOML), ohne gerätespezifische Werte wie die Fensterposition.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsExport {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub general: Option<GeneralSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patterns: Option<Vec<SavedPattern>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipes: Option<Vec<Recipe>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<Vec<SettingsProfile>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralSettings {
+    pub is_dark_mode: Option<bool>,
+    pub language: Option<String>,
+    pub trim_line: Option<bool>,
+    pub trim_parts: Option<bool>,
+    pub font_size: Option<String>,
+    pub show_line_numbers: Option<bool>,
+    pub show_empty_lines: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSummary {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub renamed: Vec<String>,
+    pub kept: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub general_updated: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+enum Format {
+    Json,
+    Toml,
+}
+
+fn format_for(path: &str) -> Format {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("toml") => Format::Toml,
+        _ => Format::Json,
+    }
+}
+
+impl SettingsExport {
+    /// Leere `sections` bedeutet alles exportieren.
+    pub fn from_settings(settings: &SaveAppSettings, sections: &[SettingsSection]) -> Self {
+        let mut settings = settings.clone();
+        settings.sync_active_profile();
+        let wants = |section| sections.is_empty() || sections.contains(&section);
+
+        SettingsExport {
+            schema_version: SETTINGS_VERSION,
+            general: wants(SettingsSection::General).then(|| GeneralSettings {
+                is_dark_mode: settings.is_dark_mode,
+                language: settings.language.clone(),
+                trim_line: settings.trim_line,
+                trim_parts: settings.trim_parts,
+                font_size: settings.font_size.clone(),
+                show_line_numbers: settings.show_line_numbers,
+                show_empty_lines: settings.show_empty_lines,
+            }),
+            patterns: wants(SettingsSection::Patterns).then(|| settings.saved_patterns.clone().unwrap_or_default()),
+            recipes: wants(SettingsSection::Recipes).then(|| settings.recipes.clone().unwrap_or_default()),
+            profiles: wants(SettingsSection::Profiles).then(|| settings.profiles.clone().unwrap_or_default()),
+        }
+    }
+
+    pub fn write(&self, file_path: &str) -> Result<(), String> {
+        let data = match format_for(file_path) {
+            Format::Toml => toml::to_string_pretty(&self.without_nulls())
+                .map_err(|e| format!("⚠ TOML-Export fehlgeschlagen: {}", e))?,
+            Format::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string())?,
+        };
+        fs::write(file_path, data).map_err(|e| format!("Fehler beim Exportieren der Settings: {:?}", e))
+    }
+
+    /// TOML kennt kein `null`. Recipe-Argumente mit `null` bedeuten dasselbe wie ein fehlendes Argument
+    /// und werden deshalb weggelassen.
+    fn without_nulls(&self) -> Self {
+        let recipes = self.recipes.clone().map(|mut recipes| {
+            for step in recipes.iter_mut().flat_map(|recipe| recipe.steps.iter_mut()) {
+                step.args.retain(|_, value| !value.is_null());
+                step.args.values_mut().for_each(strip_nulls);
+            }
+            recipes
+        });
+        SettingsExport {
+            schema_version: self.schema_version,
+            general: self.general.clone(),
+            patterns: self.patterns.clone(),
+            recipes,
+            profiles: self.profiles.clone(),
+        }
+    }
+
+    pub fn read(file_path: &str) -> Result<Self, String> {
+        let data = fs::read_to_string(file_path).map_err(|e| format!("Fehler beim Lesen der Datei: {:?}", e))?;
+        let export: Self = match format_for(file_path) {
+            Format::Toml => toml::from_str(&data).map_err(|e| format!("⚠ Ungültige TOML-Datei: {}", e))?,
+            Format::Json => serde_json::from_str(&data).map_err(|e| format!("⚠ Ungültige JSON-Datei: {}", e))?,
+        };
+        if export.schema_version > SETTINGS_VERSION {
+            return Err(format!(
+                "⚠ Die Datei stammt aus einer neueren Version ({}) und kann nicht importiert werden",
+                export.schema_version
+            ));
+        }
+        Ok(export)
+    }
+
+    /// Führt den Import in die bestehenden Settings zusammen und beschreibt, was sich geändert hat.
+    pub fn merge_into(self, settings: &mut SaveAppSettings, strategy: ConflictStrategy) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        settings.sync_active_profile();
+
+        if let Some(general) = self.general {
+            merge_general(settings, general, strategy, &mut summary);
+        }
+        if let Some(patterns) = self.patterns {
+            let mine = settings.saved_patterns.get_or_insert_with(Vec::new);
+            merge_named(mine, patterns, strategy, "pattern", |p| &mut p.name, &mut summary);
+        }
+        if let Some(recipes) = self.recipes {
+            let mine = settings.recipes.get_or_insert_with(Vec::new);
+            merge_named(mine, recipes, strategy, "recipe", |r| &mut r.name, &mut summary);
+            clear_duplicate_hotkeys(mine, &mut summary);
+        }
+
+        // Importierte Werte erst ins aktive Profil schreiben, sonst überschreibt `apply_profile` sie wieder
+        settings.sync_active_profile();
+        if let Some(profiles) = self.profiles {
+            let mine = settings.profiles.get_or_insert_with(Vec::new);
+            merge_named(mine, profiles, strategy, "profile", |p| &mut p.name, &mut summary);
+        }
+        let active = settings.active_profile_name().to_string();
+        let _ = settings.apply_profile(&active);
+        summary
+    }
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => {
+            items.retain(|v| !v.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+fn merge_general(
+    settings: &mut SaveAppSettings,
+    theirs: GeneralSettings,
+    strategy: ConflictStrategy,
+    summary: &mut MergeSummary,
+) {
+    fn apply<T: Clone + PartialEq>(
+        name: &str,
+        mine: &mut Option<T>,
+        theirs: Option<T>,
+        take_theirs: bool,
+        summary: &mut MergeSummary,
+    ) {
+        let Some(value) = theirs else { return };
+        if mine.as_ref() == Some(&value) {
+            return;
+        }
+        if mine.is_none() || take_theirs {
+            *mine = Some(value);
+            summary.general_updated.push(name.to_string());
+        } else {
+            summary.kept.push(format!("setting:{}", name));
+        }
+    }
+
+    let take = strategy == ConflictStrategy::TakeTheirs;
+    apply("isDarkMode", &mut settings.is_dark_mode, theirs.is_dark_mode, take, summary);
+    apply("language", &mut settings.language, theirs.language, take, summary);
+    apply("trimLine", &mut settings.trim_line, theirs.trim_line, take, summary);
+    apply("trimParts", &mut settings.trim_parts, theirs.trim_parts, take, summary);
+    apply("fontSize", &mut settings.font_size, theirs.font_size, take, summary);
+    apply("showLineNumbers", &mut settings.show_line_numbers, theirs.show_line_numbers, take, summary);
+    apply("showEmptyLines", &mut settings.show_empty_lines, theirs.show_empty_lines, take, summary);
+}
+
+/// Gemeinsame Merge-Logik für alle Listen mit eindeutigem Namen (Patterns, Recipes, Profile).
+fn merge_named<T, F>(
+    mine: &mut Vec<T>,
+    theirs: Vec<T>,
+    strategy: ConflictStrategy,
+    kind: &str,
+    name_of: F,
+    summary: &mut MergeSummary,
+) where
+    T: Serialize,
+    F: Fn(&mut T) -> &mut String,
+{
+    for mut item in theirs {
+        let name = name_of(&mut item).clone();
+        let label = format!("{}:{}", kind, name);
+
+        let existing = mine.iter_mut().position(|m| *name_of(m) == name);
+        let Some(index) = existing else {
+            mine.push(item);
+            summary.added.push(label);
+            continue;
+        };
+
+        if serde_json::to_value(&mine[index]).ok() == serde_json::to_value(&item).ok() {
+            summary.unchanged.push(label);
+            continue;
+        }
+
+        match strategy {
+            ConflictStrategy::KeepMine => summary.kept.push(label),
+            ConflictStrategy::TakeTheirs => {
+                mine[index] = item;
+                summary.replaced.push(label);
+            }
+            ConflictStrategy::Rename => {
+                let new_name = (2..)
+                    .map(|n| format!("{} ({})", name, n))
+                    .find(|candidate| !mine.iter_mut().any(|m| name_of(m) == candidate))
+                    .unwrap_or_default();
+                *name_of(&mut item) = new_name.clone();
+                mine.push(item);
+                summary.renamed.push(format!("{} -> {}", label, new_name));
+            }
+        }
+    }
+}
+
+fn clear_duplicate_hotkeys(recipes: &mut [Recipe], summary: &mut MergeSummary) {
+    let mut seen: Vec<String> = Vec::new();
+    for recipe in recipes.iter_mut() {
+        if let Some(hotkey) = recipe.hotkey.clone() {
+            if seen.contains(&hotkey) {
+                summary.warnings.push(format!(
+                    "Hotkey '{}' von Recipe '{}' entfernt, da bereits vergeben",
+                    hotkey, recipe.name
+                ));
+                recipe.hotkey = None;
+            } else {
+                seen.push(hotkey);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::RecipeStep;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("textsplitter-transfer-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn toml_export_skips_null_args() {
+        let Value::Object(args) = json!({ "pattern": null, "records": null, "nested": { "a": null, "b": [1, null] } }) else {
+            unreachable!()
+        };
+        let recipe = Recipe {
+            name: "nulls".to_string(),
+            description: String::new(),
+            hotkey: None,
+            parameters: vec![],
+            steps: vec![RecipeStep { operation: "remove".to_string(), args }],
+        };
+        let export = SettingsExport {
+            schema_version: SETTINGS_VERSION,
+            recipes: Some(vec![recipe]),
+            ..Default::default()
+        };
+        let path = temp_path("nulls.toml");
+        export.write(&path).unwrap();
+
+        let imported = SettingsExport::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let args = &imported.recipes.unwrap()[0].steps[0].args;
+        assert_eq!(Value::Object(args.clone()), json!({ "nested": { "b": [1] } }));
+    }
+
+    #[test]
+    fn import_rejects_newer_schema() {
+        let path = temp_path("newer.json");
+        fs::write(&path, format!("{{\"schemaVersion\": {}}}", SETTINGS_VERSION + 1)).unwrap();
+        let result = SettingsExport::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
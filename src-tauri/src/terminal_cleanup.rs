@@ -0,0 +1,257 @@
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{LineEnding, LineEndingInfo, ProcessedText, TextProcessor};
+
+lazy_static! {
+    /// CSI (`ESC [ ... final`), OSC (`ESC ] ... BEL/ST`), DCS/SOS/PM/APC-Strings, einfache `ESC x`-Sequenzen
+    /// und 8-Bit-CSI. Außerdem die Steuerzeichen, die ein Terminal beim Rendern auswertet.
+    static ref TOKEN: Regex = Regex::new(concat!(
+        r"(?:\x1b\[[0-?]*[ -/]*[@-~]",
+        r"|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)",
+        r"|\x1b[PX^_][^\x1b]*\x1b\\",
+        r"|\x1b[ -/]*[0-~]",
+        r"|\x{9b}[0-?]*[ -/]*[@-~])",
+        r"|[\r\x08\t]",
+    ))
+    .unwrap();
+
+    /// Balken in Klammern oder Pipes wie `[=====>    ]` oder `|████░░░░|`. Freie Balken (`█████`, `#####`)
+    /// zählen nur mit Prozent, Zähler, Rate oder ETA in derselben Zeile, damit Tabellenrahmen und
+    /// Trennlinien (`──────`, `##########`, `|---|`) erhalten bleiben. Außerdem Prozentangaben mit Zähler/Rate/ETA.
+    static ref PROGRESS: Regex = Regex::new(concat!(
+        r"\[[=#>\-. ]{5,}\]",
+        r"|[\[|](?=[^\]|]{4,}[\]|])[█▉▊▋▌▍▎▏░▒▓■□━#=>]+[ ░▒▓□.\-]*[\]|]",
+        r"|(?:[█▉▊▋▌▍▎▏░▒▓■□━─]{4,}|#{10,}).*(?:\d{1,3}(?:[.,]\d+)?\s?%|\d+/\d+|ETA|eta|it/s|[kMG]i?B/s)",
+        r"|(?:\d{1,3}(?:[.,]\d+)?\s?%|\d+/\d+|ETA|eta|it/s|[kMG]i?B/s).*(?:[█▉▊▋▌▍▎▏░▒▓■□━─]{4,}|#{10,})",
+        r"|\d{1,3}(?:[.,]\d+)?\s?%.*(?:\d+/\d+|ETA|eta|it/s|[kMG]i?B/s)",
+        r"|(?:\d+/\d+|ETA|eta|it/s|[kMG]i?B/s).*\d{1,3}(?:[.,]\d+)?\s?%",
+    ))
+    .unwrap();
+
+    static ref PERCENT: Regex = Regex::new(r"\d{1,3}(?:[.,]\d+)?\s?%").unwrap();
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanedText {
+    pub text: String,
+    pub sequences_removed: usize,
+    /// Zeilen, in denen `\r` oder Backspace vorherigen Inhalt überschrieben hat
+    pub overwritten_lines: usize,
+    pub progress_lines_removed: usize,
+}
+
+impl ProcessedText<CleanedText> {
+    pub fn with_line_endings(mut self, endings: &LineEndingInfo, preserve: bool) -> Self {
+        if preserve {
+            self.result_text.text = TextProcessor::restore_line_endings(&self.result_text.text, endings);
+        }
+        self
+    }
+}
+
+/// Zeilenenden der Terminal-Ausgabe: ein einzelnes `\r` springt nur an den Zeilenanfang und zählt nicht als Zeilenende.
+pub fn detect_line_endings(text: &str) -> LineEndingInfo {
+    let mut endings = TextProcessor::detect_line_endings(text);
+    if endings.dominant == Some(LineEnding::Cr) {
+        endings.dominant = Some(if endings.crlf > endings.lf { LineEnding::Crlf } else { LineEnding::Lf });
+    }
+    endings.trailing_newline = text.ends_with('\n');
+    endings
+}
+
+/// Eine Zeile so, wie ein Terminal sie am Ende anzeigen würde.
+struct RenderedLine {
+    cells: Vec<char>,
+    cursor: usize,
+    sequences: usize,
+    overwritten: bool,
+    /// Per `\r` an den Zeilenanfang gesprungen, wie es Fortschrittsanzeigen beim Neuzeichnen tun
+    redrawn: bool,
+}
+
+impl RenderedLine {
+    fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            cursor: 0,
+            sequences: 0,
+            overwritten: false,
+            redrawn: false,
+        }
+    }
+
+    fn write(&mut self, c: char) {
+        if self.cursor < self.cells.len() {
+            self.cells[self.cursor] = c;
+            self.overwritten = true;
+        } else {
+            self.cells.push(c);
+        }
+        self.cursor += 1;
+    }
+
+    fn write_str(&mut self, text: &str) {
+        // Reste, die keine vollständige Sequenz sind (z.B. abgeschnittenes `ESC [` oder BEL),
+        // entfernt strip-ansi-escapes und sie zählen als eine Sequenz
+        let plain = if text.contains(['\x1b', '\u{9b}']) || text.chars().any(char::is_control) {
+            self.sequences += 1;
+            strip_ansi_escapes::strip_str(text)
+        } else {
+            text.to_string()
+        };
+        plain.chars().for_each(|c| self.write(c));
+    }
+
+    /// Wertet die Sequenzen aus, die den sichtbaren Zeileninhalt verändern (Erase in Line).
+    fn apply_sequence(&mut self, sequence: &str) {
+        self.sequences += 1;
+        match sequence.strip_prefix("\x1b[").or_else(|| sequence.strip_prefix('\u{9b}')) {
+            Some("K") | Some("0K") => self.cells.truncate(self.cursor),
+            Some("2K") => {
+                self.cells.clear();
+                self.cursor = 0;
+            }
+            Some("1K") => {
+                let end = self.cursor.min(self.cells.len());
+                self.cells[..end].iter_mut().for_each(|c| *c = ' ');
+            }
+            _ => {}
+        }
+    }
+
+    fn render(line: &str) -> Self {
+        let mut rendered = Self::new();
+        let mut last = 0;
+
+        for token in TOKEN.find_iter(line).filter_map(Result::ok) {
+            rendered.write_str(&line[last..token.start()]);
+            last = token.end();
+
+            match token.as_str() {
+                "\r" => {
+                    rendered.redrawn |= !rendered.cells.is_empty();
+                    rendered.cursor = 0;
+                }
+                "\x08" => rendered.cursor = rendered.cursor.saturating_sub(1),
+                "\t" => rendered.write('\t'),
+                sequence => rendered.apply_sequence(sequence),
+            }
+        }
+        rendered.write_str(&line[last..]);
+        rendered
+    }
+
+    fn into_string(self) -> String {
+        let text: String = self.cells.into_iter().collect();
+        // Beim Überschreiben bleiben oft Leerzeichen vom Löschen des alten Inhalts stehen
+        if self.overwritten {
+            text.trim_end().to_string()
+        } else {
+            text
+        }
+    }
+}
+
+/// Entfernt ANSI/VT-Escape-Sequenzen und rendert `\r`/Backspace wie ein Terminal,
+/// sodass bei neu gezeichneten Zeilen nur der letzte Stand übrig bleibt.
+pub fn clean_terminal_output(
+    text: &str,
+    removeprogress: bool,
+    op: &Operation,
+) -> Result<ProcessedText<CleanedText>, OperationError> {
+    let mut collector = MetricsCollector::start("clean_terminal_output", text.len());
+
+    // `\r` vor `\n` ist ein CRLF-Zeilenende und kein Überschreiben
+    let lines: Vec<&str> = text.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).collect();
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut cleaned: Vec<String> = Vec::with_capacity(total_lines);
+    let mut sequences_removed = 0;
+    let mut overwritten_lines = 0;
+    let mut progress_lines_removed = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        let rendered = RenderedLine::render(line);
+        sequences_removed += rendered.sequences;
+        if rendered.overwritten {
+            overwritten_lines += 1;
+        }
+
+        let redrawn = rendered.redrawn;
+        let visible = rendered.into_string();
+        let is_progress = PROGRESS.is_match(&visible).unwrap_or(false)
+            || (redrawn && PERCENT.is_match(&visible).unwrap_or(false));
+        if removeprogress && is_progress {
+            progress_lines_removed += 1;
+            continue;
+        }
+        cleaned.push(visible);
+    }
+
+    // Ein abschließendes `\n` erzeugt beim Splitten eine leere letzte Zeile
+    if text.ends_with('\n') && cleaned.last().is_some_and(String::is_empty) {
+        cleaned.pop();
+    }
+    collector.phase("process");
+
+    let result = cleaned.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result.len());
+
+    Ok(ProcessedText {
+        result_text: CleanedText {
+            text: result,
+            sequences_removed,
+            overwritten_lines,
+            progress_lines_removed,
+        },
+        removed_lines: progress_lines_removed,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_progress(line: &str) -> bool {
+        PROGRESS.is_match(line).unwrap()
+    }
+
+    #[test]
+    fn progress_bars_are_detected() {
+        for line in [
+            "[=====>    ] 50%",
+            "|████░░░░| building",
+            "[##########          ]",
+            "━━━━━━━━━━━━ 12/40 ETA 0:01",
+            "##########      45%",
+            "downloading 45% 3.2MiB/s",
+        ] {
+            assert!(is_progress(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn borders_and_separators_are_kept() {
+        for line in [
+            "┌──────────┬──────┐",
+            "├──────────┼──────┤",
+            "##########",
+            "## Section ########################",
+            "|------|------|",
+            "| :--- | ---: |",
+            "+------+------+",
+            "──────────────────",
+        ] {
+            assert!(!is_progress(line), "{}", line);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use serde::Serialize;
 use std::collections::HashMap;
 
-use crate::text_processor::{get_backend_metrics, ProcessedText};
+use crate::operations::{Operation, OperationError};
+use crate::metrics::MetricsCollector;
+use crate::text_processor::ProcessedText;
+use crate::unicode_inspector::{LineNormalizer, NormalizeOptions};
 
 #[derive(Debug, Clone, Serialize)]
 pub enum DiffType {
@@ -18,19 +21,36 @@ pub struct DiffResult {
     pub line_number: usize,
 }
 
-pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Result<Vec<DiffResult>, String> {
+/// Mit `normalize` werden nur die Vergleichsschlüssel normalisiert, die Ergebnisse zeigen die Originalzeilen.
+pub fn compare_texts(
+    oldtext: &str,
+    newtext: &str,
+    ignorewhitespace: bool,
+    normalize: Option<&NormalizeOptions>,
+    op: &Operation,
+) -> Result<Vec<DiffResult>, OperationError> {
+    let normalizer = normalize.map(LineNormalizer::new).transpose()?;
+
     // Split texts into lines
     let old_lines: Vec<&str> = oldtext.lines().collect();
     let new_lines: Vec<&str> = newtext.lines().collect();
+
+    // Progress counts every line once per pass (count, unchanged, position map, classify)
+    let total_steps = (old_lines.len() + new_lines.len()) * 3 + old_lines.len().min(new_lines.len());
+    let mut steps = 0;
     
     let mut result = Vec::new();
     
     // Helper function to normalize line content based on whitespace preference
     let normalize = |line: &str| -> String {
+        let line = match &normalizer {
+            Some(normalizer) => normalizer.apply(line),
+            None => line.to_string(),
+        };
         if ignorewhitespace {
             line.split_whitespace().collect::<Vec<&str>>().join(" ")
         } else {
-            line.to_string()
+            line
         }
     };
     
@@ -39,11 +59,15 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     let mut new_line_counts: HashMap<String, usize> = HashMap::new();
     
     for line in &old_lines {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         let key = normalize(line);
         *old_line_counts.entry(key).or_insert(0) += 1;
     }
     
     for line in &new_lines {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         let key = normalize(line);
         *new_line_counts.entry(key).or_insert(0) += 1;
     }
@@ -54,6 +78,8 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     
     // First pass: Find unchanged lines (same content, same position)
     for i in 0..old_lines.len().min(new_lines.len()) {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         let old_key = normalize(&old_lines[i]);
         let new_key = normalize(&new_lines[i]);
         
@@ -74,6 +100,8 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     
     // Build position maps
     for (i, line) in old_lines.iter().enumerate() {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         if !old_matched[i] {
             let key = normalize(line);
             old_positions.entry(key).or_insert_with(Vec::new).push(i);
@@ -81,6 +109,8 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     }
     
     for (i, line) in new_lines.iter().enumerate() {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         if !new_matched[i] {
             let key = normalize(line);
             new_positions.entry(key).or_insert_with(Vec::new).push(i);
@@ -111,6 +141,8 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     
     // Third pass: Handle added and removed lines
     for (i, &matched) in old_matched.iter().enumerate() {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         if !matched {
             result.push(DiffResult {
                 text: old_lines[i].to_string(),
@@ -121,6 +153,8 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     }
     
     for (i, &matched) in new_matched.iter().enumerate() {
+        steps += 1;
+        op.progress(steps, total_steps)?;
         if !matched {
             result.push(DiffResult {
                 text: new_lines[i].to_string(),
@@ -152,18 +186,23 @@ pub fn compare_texts(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Re
     Ok(result)
 }
 
-pub fn get_diff_json(oldtext: &str, newtext: &str, ignorewhitespace: bool) -> Result<ProcessedText<String>, String> {
-    let before_mem = memory_stats::memory_stats().unwrap();
-    let start_time = std::time::Instant::now();
+pub fn get_diff_json(
+    oldtext: &str,
+    newtext: &str,
+    ignorewhitespace: bool,
+    normalize: Option<&NormalizeOptions>,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("get_text_diff", oldtext.len() + newtext.len());
 
-    let diffs = compare_texts(oldtext, newtext, ignorewhitespace)?;
+    let diffs = compare_texts(oldtext, newtext, ignorewhitespace, normalize, op)?;
+    collector.phase("process");
 
     let result_json = serde_json::to_string(&diffs)
         .map_err(|e| format!("JSON serialization error: {}", e))?;
+    collector.phase("serialize");
+    let metrics = collector.finish(result_json.len());
 
-    let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
-    let metrics = get_backend_metrics(execution_time, before_mem);
-    
     Ok(ProcessedText {
         result_text: result_json,
         removed_lines: 0,
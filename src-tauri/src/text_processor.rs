@@ -1,19 +1,17 @@
 use fancy_regex::Regex;
 use serde::Serialize;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use memory_stats::{memory_stats, MemoryStats};
-use thousands::Separable;
+use crate::metrics::{BackendMetrics, MetricsCollector};
+use crate::operations::{Operation, OperationError};
+use crate::unicode_inspector::{LineNormalizer, NormalizeOptions};
 
 pub struct TextProcessor;
 
 
-#[derive(Clone, Debug, Serialize)]
-pub struct BackendMetrics {
-    execution_time_ms: f64,
-    memory_usage_kb: usize,
-    memory_usage_str: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcessedText<T> {
     pub(crate) result_text: T,
@@ -21,63 +19,222 @@ pub struct ProcessedText<T> {
     pub(crate) metrics: BackendMetrics,
 }
 
-pub fn get_backend_metrics(execution_time: f64, before_mem: MemoryStats) -> BackendMetrics {
-    let after_mem = memory_stats().unwrap();
+impl<T> ProcessedText<T> {
+    /// Ersetzt das Ergebnis, Metriken und entfernte Zeilen bleiben erhalten.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ProcessedText<U> {
+        ProcessedText {
+            result_text: f(self.result_text),
+            removed_lines: self.removed_lines,
+            metrics: self.metrics,
+        }
+    }
+}
+
+impl ProcessedText<String> {
+    /// Stellt nach der Verarbeitung den Zeilenende-Stil der Eingabe wieder her (falls gewünscht).
+    pub fn with_line_endings(mut self, endings: &LineEndingInfo, preserve: bool) -> Self {
+        if preserve {
+            self.result_text = TextProcessor::restore_line_endings(&self.result_text, endings);
+        }
+        self
+    }
+}
 
-    BackendMetrics {
-        execution_time_ms: execution_time,
-        memory_usage_kb: after_mem.physical_mem / 1024,
-        memory_usage_str: (after_mem.physical_mem - before_mem.physical_mem).separate_with_commas(),
+impl ProcessedText<Vec<String>> {
+    pub fn with_line_endings(mut self, endings: &LineEndingInfo, preserve: bool) -> Self {
+        if preserve {
+            for column in self.result_text.iter_mut() {
+                *column = TextProcessor::restore_line_endings(column, endings);
+            }
+        }
+        self
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl LineEnding {
+    pub fn parse(ending: &str) -> Result<Self, String> {
+        match ending.to_lowercase().as_str() {
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::Crlf),
+            "cr" => Ok(Self::Cr),
+            _ => Err(format!("⚠ Unbekanntes Zeilenende '{}'", ending)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+            Self::Cr => "\r",
+        }
+    }
+}
+
+/// Ergebnis der Zeilenende-Erkennung. `mixed_lines` enthält die Zeilennummern (ab 1),
+/// deren Ende vom vorherrschenden Stil abweicht.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineEndingInfo {
+    /// "lf", "crlf", "cr", "mixed" oder "none" (einzeilig ohne Zeilenende)
+    pub style: &'static str,
+    pub dominant: Option<LineEnding>,
+    pub lf: usize,
+    pub crlf: usize,
+    pub cr: usize,
+    pub trailing_newline: bool,
+    pub mixed_lines: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedLineEndings {
+    pub text: String,
+    pub found: LineEndingInfo,
+}
+
 impl TextProcessor {
 
+    /// Zählt LF, CRLF und einzelne CR. Bei Gleichstand gewinnt LF vor CRLF vor CR.
+    pub fn detect_line_endings(text: &str) -> LineEndingInfo {
+        let bytes = text.as_bytes();
+        let mut endings: Vec<LineEnding> = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                    endings.push(LineEnding::Crlf);
+                    i += 1;
+                }
+                b'\r' => endings.push(LineEnding::Cr),
+                b'\n' => endings.push(LineEnding::Lf),
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let count = |ending| endings.iter().filter(|e| **e == ending).count();
+        let (lf, crlf, cr) = (count(LineEnding::Lf), count(LineEnding::Crlf), count(LineEnding::Cr));
+        let dominant = [(LineEnding::Lf, lf), (LineEnding::Crlf, crlf), (LineEnding::Cr, cr)]
+            .into_iter()
+            .filter(|(_, n)| *n > 0)
+            .max_by(|(_, a), (_, b)| a.cmp(b).then(Ordering::Greater))
+            .map(|(ending, _)| ending);
+
+        let mixed_lines: Vec<usize> = endings
+            .iter()
+            .enumerate()
+            .filter(|(_, ending)| Some(**ending) != dominant)
+            .map(|(index, _)| index + 1)
+            .collect();
+
+        let style = match dominant {
+            None => "none",
+            Some(_) if !mixed_lines.is_empty() => "mixed",
+            Some(LineEnding::Lf) => "lf",
+            Some(LineEnding::Crlf) => "crlf",
+            Some(LineEnding::Cr) => "cr",
+        };
+
+        LineEndingInfo {
+            style,
+            dominant,
+            lf,
+            crlf,
+            cr,
+            trailing_newline: text.ends_with(['\n', '\r']),
+            mixed_lines,
+        }
+    }
+
+    /// Bringt alle Zeilenenden auf LF, damit `lines()` auch reine CR-Dateien korrekt trennt.
+    pub fn normalize_line_endings(text: &str) -> (Cow<'_, str>, LineEndingInfo) {
+        let info = Self::detect_line_endings(text);
+        if info.crlf == 0 && info.cr == 0 {
+            return (Cow::Borrowed(text), info);
+        }
+        (Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n")), info)
+    }
+
+    /// Gegenstück zu `normalize_line_endings` für das Ergebnis einer Operation (ohne Zeilenende am Schluss):
+    /// LF wird zum vorherrschenden Stil der Eingabe, ein abschließendes Zeilenende wird wieder angehängt.
+    pub fn restore_line_endings(text: &str, endings: &LineEndingInfo) -> String {
+        let ending = endings.dominant.unwrap_or(LineEnding::Lf).as_str();
+        let mut restored = if ending == "\n" {
+            text.to_string()
+        } else {
+            text.replace('\n', ending)
+        };
+        if endings.trailing_newline && !restored.is_empty() {
+            restored.push_str(ending);
+        }
+        restored
+    }
+
+    /// Wandelt alle Zeilenenden in `target` ("lf", "crlf", "cr") um und meldet, welche Zeilen vorher abwichen.
+    pub fn convert_line_endings(
+        text: &str,
+        target: &str,
+        op: &Operation,
+    ) -> Result<ProcessedText<ConvertedLineEndings>, OperationError> {
+        let mut collector = MetricsCollector::start("convert_line_endings", text.len());
+
+        let target = LineEnding::parse(target)?;
+        let (normalized, found) = Self::normalize_line_endings(text);
+        collector.phase("parse");
+
+        op.check()?;
+        // Der normalisierte Text enthält sein abschließendes Zeilenende noch
+        let converted = LineEndingInfo {
+            dominant: Some(target),
+            trailing_newline: false,
+            ..found.clone()
+        };
+        let result = Self::restore_line_endings(&normalized, &converted);
+        collector.phase("process");
+        let metrics = collector.finish(result.len());
+
+        Ok(ProcessedText {
+            result_text: ConvertedLineEndings { text: result, found },
+            removed_lines: 0,
+            metrics,
+        })
+    }
+
     pub fn process_text_block_to_column_strings(
         text: &str,
         delimiter: &str,
         trimparts: bool,
         useregex: bool,
-    ) -> Result<ProcessedText<Vec<String>>, String> {
-        let before_mem = memory_stats::memory_stats().unwrap();
-        let start_time = std::time::Instant::now();
+        op: &Operation,
+    ) -> Result<ProcessedText<Vec<String>>, OperationError> {
+        let mut collector = MetricsCollector::start("process_text_block", text.len());
 
-        let regex = if useregex {
-            Some(Regex::new(delimiter).map_err(|_| "⚠ Ungültiges Regex-Pattern".to_string())?)
-        } else {
-            None
-        };
+        let regex = Self::build_split_regex(delimiter, useregex)?;
+        collector.phase("parse");
 
-        let mut columns: Vec<Vec<String>> = Vec::new();
+        let columns = Self::split_columns(text, op, |line| {
+            Self::split_line(line, delimiter, regex.as_ref())
+                .into_iter()
+                .map(|part| if trimparts { part.trim().to_string() } else { part.to_string() })
+                .collect()
+        })?;
 
-        for line in text.lines() {
-            let parts: Vec<&str> = if let Some(ref reg) = regex {
-                reg.split(line).map(|s| s.unwrap_or("")).collect::<Vec<_>>()
-            } else {
-                line.split(delimiter).collect::<Vec<_>>()
-            };
+        collector.phase("process");
 
-            parts.iter().enumerate().for_each(|(i, part)| {
-                if columns.len() <= i {
-                    columns.push(Vec::new());
-                }
-                columns[i].push(if trimparts {
-                    part.trim().to_string()
-                } else {
-                    part.to_string()
-                });
-            });
-
-            for col in columns.iter_mut().skip(parts.len()) {
-                col.push(String::new());
-            }
-        }
+        let result_text: Vec<String> = columns.into_iter().map(|col| col.join("\n")).collect();
+        collector.phase("serialize");
+        let metrics = collector.finish(result_text.iter().map(String::len).sum());
 
-        let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
-        let metrics = get_backend_metrics(execution_time, before_mem);
-        
         Ok(ProcessedText {
-            result_text: columns.into_iter().map(|col| col.join("\n")).collect(),
+            result_text,
             removed_lines: 0,
             metrics,
         })
@@ -118,44 +275,31 @@ impl TextProcessor {
         filter_mode: &str,
         casesensitive: bool,
         splitmatches: bool,
-    ) -> Result<ProcessedText<String>, String> {
-        let before_mem = memory_stats::memory_stats().unwrap();
-        let start_time = std::time::Instant::now();
-
-        let modified_pattern = if casesensitive {
-            pattern.to_string()
-        } else {
-            format!("(?i){}", pattern) 
-        };
-
-        let regex =
-            Regex::new(&modified_pattern).map_err(|_| "⚠ Fehlerhafte Regex-Syntax".to_string())?;
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        let mut collector = MetricsCollector::start("filter_text", text.len());
+
+        let regex = Self::build_filter_regex(pattern, casesensitive)?;
+
+        let total_lines = text.lines().count();
+        collector.phase("parse");
+        let mut processed_lines: Vec<String> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            op.progress(index, total_lines)?;
+            let filtered = Self::filter_line(line, &regex, filter_mode, splitmatches);
+            if !filtered.is_empty() {
+                processed_lines.push(filtered);
+            }
+        }
 
-        let processed_lines: Vec<String> = text
-            .lines()
-            .map(|line| {
-                if line.trim().is_empty() {
-                    return "".to_string(); 
-                }
+        collector.phase("process");
 
-                match filter_mode {
-                    "remove" => regex.replace_all(line, "").to_string(),
-                    _ => regex
-                        .find_iter(line)
-                        .filter_map(Result::ok) 
-                        .map(|m| m.as_str().to_string())
-                        .collect::<Vec<_>>()
-                        .join(if splitmatches { "\n" } else { "" }),
-                }
-            })
-            .filter(|s| !s.is_empty()) 
-            .collect();
+        let result_text = processed_lines.join("\n");
+        collector.phase("serialize");
+        let metrics = collector.finish(result_text.len());
 
-        let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
-        let metrics = get_backend_metrics(execution_time, before_mem);
-        
         Ok(ProcessedText {
-            result_text: processed_lines.join("\n"),
+            result_text,
             removed_lines: 0,
             metrics,
         })
@@ -163,6 +307,7 @@ impl TextProcessor {
 
     /// Diese Funktion entfernt Zeilen aus dem Text, entweder durch Duplikate oder durch Übereinstimmungen mit einem Muster (entweder mit regulären Ausdrücken oder normaler Textsuche).
     /// Wenn der Modus duplicates gewählt wird, werden doppelte Zeilen basierend auf einem Schlüssel (der ggf. bei Bedarf auf Kleinbuchstaben reduziert wird) entfernt.
+    #[allow(clippy::too_many_arguments)]
     pub fn remove_lines_internal(
         text: &str,
         pattern: &str,
@@ -170,86 +315,599 @@ impl TextProcessor {
         casesensitive: bool,
         useregex: bool,
         trimparts: bool,
-    ) -> Result<ProcessedText<String>, String> {
+        normalize: Option<&NormalizeOptions>,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        let mut collector = MetricsCollector::start("remove_lines", text.len());
+
         let lines: Vec<&str> = text.lines().collect();
         let total_lines = lines.len(); // 🏆 Anzahl der Originalzeilen
 
-        let before_mem = memory_stats::memory_stats().unwrap();
-        let start_time = std::time::Instant::now();
-
-        let processed_lines: Vec<String> = if removemode == "duplicates" {
-            let mut seen = std::collections::HashSet::new();
-            lines
-                .iter()
-                .filter_map(|&line| {
-                    let processed_line = if trimparts { line.trim() } else { line };
-                    let key = if casesensitive {
-                        processed_line.to_string()
-                    } else {
-                        processed_line.to_lowercase()
-                    };
-
-                    if seen.contains(&key) {
-                        None
-                    } else {
-                        seen.insert(key);
-                        Some(processed_line.to_string())
-                    }
-                })
-                .collect()
+        let mut remover =
+            LineRemover::new(pattern, removemode, casesensitive, useregex, trimparts)?.with_normalization(normalize)?;
+        collector.phase("parse");
+        let mut processed_lines: Vec<String> = Vec::new();
+        for (index, &line) in lines.iter().enumerate() {
+            op.progress(index, total_lines)?;
+            if let Some(kept) = remover.apply(line) {
+                processed_lines.push(kept.to_string());
+            }
+        }
+
+        collector.phase("process");
+
+        let processed_text = processed_lines.join("\n");
+        collector.phase("serialize");
+        let metrics = collector.finish(processed_text.len());
+
+        Ok(ProcessedText {
+            result_text: processed_text,
+            removed_lines: total_lines - processed_lines.len(),
+            metrics,
+        })
+    }
+
+    /// Sortiert die Zeilen nach dem gewählten Modus (gleiche Modi wie die SortPage im Frontend).
+    pub fn sort_lines_internal(
+        text: &str,
+        sortmode: &str,
+        casesensitive: bool,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        let mut collector = MetricsCollector::start("sort_text", text.len());
+
+        let mode = SortMode::parse(sortmode)?;
+        let mut lines: Vec<&str> = text.lines().collect();
+        collector.phase("parse");
+
+        op.check()?;
+        lines.sort_by(|a, b| mode.compare(a, b, casesensitive));
+        op.check()?;
+        collector.phase("process");
+
+        let result_text = lines.join("\n");
+        collector.phase("serialize");
+        let metrics = collector.finish(result_text.len());
+
+        Ok(ProcessedText {
+            result_text,
+            removed_lines: 0,
+            metrics,
+        })
+    }
+
+    /// Bricht Zeilen, die breiter als `width` Spalten sind (Unicode-Anzeigebreite), an Leerzeichen um.
+    /// Einrückung bleibt für Folgezeilen erhalten. Ohne `breakwords` bleiben überlange Wörter ganz.
+    pub fn wrap_lines(
+        text: &str,
+        width: usize,
+        breakwords: bool,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        if width == 0 {
+            return Err("⚠ Die Zeilenbreite muss größer als 0 sein".to_string().into());
+        }
+        Self::transform_lines("wrap_text", text, op, |line| {
+            if line.width() <= width {
+                return line.to_string();
+            }
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let indent = if indent.width() < width / 2 { indent } else { "" };
+            wrap_words(line.split_whitespace(), width - indent.width(), breakwords)
+                .into_iter()
+                .map(|wrapped| format!("{}{}", indent, wrapped))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    /// Fügt die Zeilen jedes Absatzes (getrennt durch Leerzeilen) zu einer Zeile zusammen und bricht sie
+    /// bei `width` > 0 neu um. Gemeinsame Präfixe aus `>`/Leerzeichen (E-Mail-Zitate, Einrückung) bleiben erhalten.
+    pub fn reflow_paragraphs(text: &str, width: usize, op: &Operation) -> Result<ProcessedText<String>, OperationError> {
+        let mut collector = MetricsCollector::start("reflow_text", text.len());
+
+        let lines: Vec<&str> = text.lines().collect();
+        let total_lines = lines.len();
+        collector.phase("parse");
+
+        let is_blank = |line: &str| line.chars().all(|c| c.is_whitespace() || c == '>');
+        let mut result: Vec<String> = Vec::new();
+        let mut index = 0;
+        while index < total_lines {
+            op.progress(index, total_lines)?;
+            if is_blank(lines[index]) {
+                result.push(lines[index].to_string());
+                index += 1;
+                continue;
+            }
+
+            let end = (index..total_lines).find(|&i| is_blank(lines[i])).unwrap_or(total_lines);
+            let paragraph = &lines[index..end];
+            let prefix = common_quote_prefix(paragraph);
+            let words = paragraph.iter().flat_map(|line| line[prefix.len()..].split_whitespace());
+
+            if width == 0 {
+                result.push(format!("{}{}", prefix, words.collect::<Vec<_>>().join(" ")));
+            } else {
+                let available = width.saturating_sub(prefix.width()).max(1);
+                result.extend(wrap_words(words, available, false).into_iter().map(|l| format!("{}{}", prefix, l)));
+            }
+            index = end;
+        }
+        collector.phase("process");
+
+        let result_text = result.join("\n");
+        collector.phase("serialize");
+        let metrics = collector.finish(result_text.len());
+
+        Ok(ProcessedText {
+            result_text,
+            removed_lines: total_lines.saturating_sub(result.len()),
+            metrics,
+        })
+    }
+
+    /// Setzt `prefix` (z.B. `> ` oder `// `) vor jede Zeile; mit `skipempty` bleiben Leerzeilen leer.
+    pub fn add_line_prefix(
+        text: &str,
+        prefix: &str,
+        skipempty: bool,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        Self::transform_lines("add_prefix", text, op, |line| {
+            if skipempty && line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+    }
+
+    /// Entfernt `prefix` am Zeilenanfang (auch ohne abschließendes Leerzeichen, z.B. `>` bei leeren Zitatzeilen).
+    /// Ohne Präfix wird die gemeinsame Einrückung aller nicht-leeren Zeilen entfernt.
+    pub fn remove_line_prefix(text: &str, prefix: &str, op: &Operation) -> Result<ProcessedText<String>, OperationError> {
+        let common_indent;
+        let prefix = if prefix.is_empty() {
+            common_indent = common_indentation(text);
+            common_indent.as_str()
+        } else {
+            prefix
+        };
+        let bare = prefix.trim_end();
+
+        Self::transform_lines("remove_prefix", text, op, |line| {
+            if let Some(rest) = line.strip_prefix(prefix) {
+                rest.to_string()
+            } else if !bare.is_empty() && line.trim_end() == bare {
+                String::new()
+            } else {
+                line.to_string()
+            }
+        })
+    }
+
+    /// Tabs → Leerzeichen bis zum nächsten Tabstopp, oder (`totabs`) führende Leerzeichen → Tabs.
+    pub fn convert_tabs(
+        text: &str,
+        tabstop: usize,
+        totabs: bool,
+        op: &Operation,
+    ) -> Result<ProcessedText<String>, OperationError> {
+        if tabstop == 0 {
+            return Err("⚠ Der Tabstopp muss größer als 0 sein".to_string().into());
+        }
+        Self::transform_lines("convert_tabs", text, op, |line| {
+            if totabs {
+                let content = line.trim_start_matches([' ', '\t']);
+                let indent = expand_tabs(&line[..line.len() - content.len()], tabstop).len();
+                format!("{}{}{}", "\t".repeat(indent / tabstop), " ".repeat(indent % tabstop), content)
+            } else {
+                expand_tabs(line, tabstop)
+            }
+        })
+    }
+
+    /// Gemeinsames Gerüst für Operationen, die jede Zeile unabhängig umwandeln.
+    fn transform_lines<F>(command: &'static str, text: &str, op: &Operation, mut f: F) -> Result<ProcessedText<String>, OperationError>
+    where
+        F: FnMut(&str) -> String,
+    {
+        let mut collector = MetricsCollector::start(command, text.len());
+
+        let lines: Vec<&str> = text.lines().collect();
+        let total_lines = lines.len();
+        collector.phase("parse");
+
+        let mut result: Vec<String> = Vec::with_capacity(total_lines);
+        for (index, line) in lines.iter().enumerate() {
+            op.progress(index, total_lines)?;
+            result.push(f(line));
+        }
+        collector.phase("process");
+
+        let result_text = result.join("\n");
+        collector.phase("serialize");
+        let metrics = collector.finish(result_text.len());
+
+        Ok(ProcessedText {
+            result_text,
+            removed_lines: 0,
+            metrics,
+        })
+    }
+
+    /// Spaltenmodell: jede Zeile wird mit `split` zerlegt, der i-te Teil landet in Spalte i.
+    /// Kürzere Zeilen werden mit leeren Zellen aufgefüllt, damit alle Spalten gleich lang sind.
+    pub(crate) fn split_columns<F>(text: &str, op: &Operation, split: F) -> Result<Vec<Vec<String>>, OperationError>
+    where
+        F: Fn(&str) -> Vec<String>,
+    {
+        let mut columns: Vec<Vec<String>> = Vec::new();
+        let total_lines = text.lines().count();
+
+        for (index, line) in text.lines().enumerate() {
+            op.progress(index, total_lines)?;
+            let parts = split(line);
+
+            for (i, part) in parts.iter().enumerate() {
+                if columns.len() <= i {
+                    // Neue Spalte: für alle bisherigen Zeilen leer
+                    columns.push(vec![String::new(); index]);
+                }
+                columns[i].push(part.clone());
+            }
+
+            for col in columns.iter_mut().skip(parts.len()) {
+                col.push(String::new());
+            }
+        }
+        Ok(columns)
+    }
+
+    pub(crate) fn build_split_regex(delimiter: &str, useregex: bool) -> Result<Option<Regex>, String> {
+        if useregex {
+            Ok(Some(Regex::new(delimiter).map_err(|_| "⚠ Ungültiges Regex-Pattern".to_string())?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn split_line<'a>(line: &'a str, delimiter: &str, regex: Option<&Regex>) -> Vec<&'a str> {
+        if let Some(reg) = regex {
+            reg.split(line).map(|s| s.unwrap_or("")).collect::<Vec<_>>()
+        } else {
+            line.split(delimiter).collect::<Vec<_>>()
+        }
+    }
+
+    pub(crate) fn build_filter_regex(pattern: &str, casesensitive: bool) -> Result<Regex, String> {
+        let modified_pattern = if casesensitive {
+            pattern.to_string()
         } else {
+            format!("(?i){}", pattern) 
+        };
+
+        Regex::new(&modified_pattern).map_err(|_| "⚠ Fehlerhafte Regex-Syntax".to_string())
+    }
+
+    /// Wendet den Filter auf eine einzelne Zeile an. Ein leerer String bedeutet, dass die Zeile entfällt.
+    pub(crate) fn filter_line(line: &str, regex: &Regex, filter_mode: &str, splitmatches: bool) -> String {
+        if line.trim().is_empty() {
+            return "".to_string(); 
+        }
+
+        match filter_mode {
+            "remove" => regex.replace_all(line, "").to_string(),
+            _ => regex
+                .find_iter(line)
+                .filter_map(Result::ok) 
+                .map(|m| m.as_str().to_string())
+                .collect::<Vec<_>>()
+                .join(if splitmatches { "\n" } else { "" }),
+        }
+    }
+}
+
+/// Entscheidet zeilenweise, ob eine Zeile behalten wird. Hält für den Modus `duplicates`
+/// die bereits gesehenen Schlüssel, damit es auch beim Streamen von Dateien funktioniert.
+pub(crate) struct LineRemover {
+    removemode: String,
+    pattern: String,
+    casesensitive: bool,
+    trimparts: bool,
+    regex: Option<Regex>,
+    seen: HashSet<String>,
+    /// Vergleicht normalisierte Zeilen, behalten wird aber die Originalzeile
+    normalizer: Option<LineNormalizer>,
+}
+
+impl LineRemover {
+    pub(crate) fn new(
+        pattern: &str,
+        removemode: &str,
+        casesensitive: bool,
+        useregex: bool,
+        trimparts: bool,
+    ) -> Result<Self, String> {
+        let regex = if useregex && removemode != "duplicates" {
             let final_pattern = if casesensitive {
                 pattern.to_string()
             } else {
                 format!("(?i){}", pattern)
             };
+            Some(Regex::new(&final_pattern).map_err(|_| "⚠ Fehlerhafte Regex-Syntax".to_string())?)
+        } else {
+            None
+        };
 
-            let matcher: Box<dyn Fn(&str) -> bool> = if useregex {
-                let regex = Regex::new(&final_pattern).unwrap();
-                Box::new(move |line| regex.is_match(line).unwrap_or(false))
-            } else {
-                Box::new(move |line| {
-                    let processed_line = if casesensitive {
-                        line
-                    } else {
-                        &line.to_lowercase()
-                    };
-                    let pattern2 = if casesensitive {
-                        pattern
-                    } else {
-                        &pattern.to_lowercase()
-                    };
-                    processed_line.contains(pattern2)
-                })
-            };
+        Ok(Self {
+            removemode: removemode.to_string(),
+            pattern: if casesensitive { pattern.to_string() } else { pattern.to_lowercase() },
+            casesensitive,
+            trimparts,
+            regex,
+            seen: HashSet::new(),
+            normalizer: None,
+        })
+    }
+
+    pub(crate) fn with_normalization(mut self, options: Option<&NormalizeOptions>) -> Result<Self, String> {
+        self.normalizer = options.map(LineNormalizer::new).transpose()?;
+        Ok(self)
+    }
+
+    fn key<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        match &self.normalizer {
+            Some(normalizer) => Cow::Owned(normalizer.apply(line)),
+            None => Cow::Borrowed(line),
+        }
+    }
+
+    /// Gibt die Zeile zurück, wenn sie behalten wird (bei `duplicates` ggf. getrimmt), sonst `None`.
+    pub(crate) fn apply<'a>(&mut self, line: &'a str) -> Option<&'a str> {
+        let key = self.key(line);
+        if self.removemode == "duplicates" {
+            let processed_line = if self.trimparts { line.trim() } else { line };
+            let key = if self.trimparts { key.trim() } else { &key };
+            let key = if self.casesensitive { key.to_string() } else { key.to_lowercase() };
+
+            return self.seen.insert(key).then_some(processed_line);
+        }
 
-            lines
-                .iter()
-                .filter_map(|&line| {
-                    if (removemode == "containing" && !matcher(line))
-                        || (removemode != "containing" && matcher(line))
-                    {
-                        Some(line.to_string())
-                    } else {
-                        None
+        let matched = self.matches(&key);
+        self.keeps(matched).then_some(line)
+    }
+
+    /// Wie `apply` für einen mehrzeiligen Datensatz: das Muster wird zeilenweise geprüft, damit `^` und `$`
+    /// an jeder Zeile greifen; nur `duplicates` vergleicht den zusammengesetzten Datensatz.
+    pub(crate) fn apply_record(&mut self, lines: &[&str]) -> Option<String> {
+        let joined = lines.join("\n");
+        if self.removemode == "duplicates" {
+            return self.apply(&joined).map(str::to_string);
+        }
+        let matched = lines.iter().any(|line| self.matches(&self.key(line)));
+        self.keeps(matched).then_some(joined)
+    }
+
+    fn keeps(&self, matched: bool) -> bool {
+        (self.removemode == "containing" && !matched) || (self.removemode != "containing" && matched)
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(line).unwrap_or(false),
+            None if self.casesensitive => line.contains(&self.pattern),
+            None => line.to_lowercase().contains(&self.pattern),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortMode {
+    Alphabetical,
+    AlphabeticalReverse,
+    Natural,
+    NaturalReverse,
+    LengthAsc,
+    LengthDesc,
+}
+
+impl SortMode {
+    pub fn parse(sortmode: &str) -> Result<Self, String> {
+        match sortmode {
+            "alphabetical" => Ok(Self::Alphabetical),
+            "alphabeticalReverse" => Ok(Self::AlphabeticalReverse),
+            "natural" => Ok(Self::Natural),
+            "naturalReverse" => Ok(Self::NaturalReverse),
+            "lengthAsc" => Ok(Self::LengthAsc),
+            "lengthDesc" => Ok(Self::LengthDesc),
+            _ => Err(format!("⚠ Unbekannter Sortiermodus '{}'", sortmode)),
+        }
+    }
+
+    pub fn compare(&self, a: &str, b: &str, casesensitive: bool) -> Ordering {
+        let fold = |s: &str| if casesensitive { s.to_string() } else { s.to_lowercase() };
+        match self {
+            Self::Alphabetical => fold(a).cmp(&fold(b)),
+            Self::AlphabeticalReverse => fold(b).cmp(&fold(a)),
+            Self::Natural => natural_cmp(&fold(a), &fold(b)),
+            Self::NaturalReverse => natural_cmp(&fold(b), &fold(a)),
+            Self::LengthAsc => a.chars().count().cmp(&b.chars().count()),
+            Self::LengthDesc => b.chars().count().cmp(&a.chars().count()),
+        }
+    }
+}
+
+/// Vergleicht Zahlenblöcke numerisch, damit "file2" vor "file10" landet.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.trim().chars().peekable();
+    let mut b_chars = b.trim().chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+                        digits.push(c);
+                        chars.next();
                     }
-                })
-                .collect()
-        };
+                    digits
+                };
+                let x_num = take_number(&mut a_chars);
+                let y_num = take_number(&mut b_chars);
+                let x_trim = x_num.trim_start_matches('0');
+                let y_trim = y_num.trim_start_matches('0');
+                let ord = x_trim.len().cmp(&y_trim.len()).then_with(|| x_trim.cmp(y_trim));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
 
-        let execution_time = start_time.elapsed().as_secs_f64() * 1000.0;
-        let processed_text = processed_lines.join("\n");
+/// Verteilt Wörter greedy auf Zeilen mit höchstens `width` Spalten Anzeigebreite.
+fn wrap_words<'a>(words: impl Iterator<Item = &'a str>, width: usize, breakwords: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in words {
+        let word_width = word.width();
+        if current_width > 0 && current_width + 1 + word_width <= width {
+            current.push(' ');
+            current.push_str(word);
+            current_width += 1 + word_width;
+            continue;
+        }
+        if current_width > 0 {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if word_width <= width || !breakwords {
+            current.push_str(word);
+            current_width = word_width;
+            continue;
+        }
+        for c in word.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if current_width + char_width > width && current_width > 0 {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += char_width;
+        }
+    }
+    if current_width > 0 || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
 
-        let metrics = get_backend_metrics(execution_time, before_mem);
-        
-        Ok(ProcessedText {
-            result_text: processed_text,
-            removed_lines: total_lines - processed_lines.len(),
-            metrics,
+/// Längstes gemeinsames Präfix aus `>`, Leerzeichen und Tabs aller Zeilen eines Absatzes.
+fn common_quote_prefix<'a>(lines: &[&'a str]) -> &'a str {
+    let lead = |line: &'a str| &line[..line.len() - line.trim_start_matches(['>', ' ', '\t']).len()];
+    let first = lead(lines[0]);
+    let common = lines[1..].iter().fold(first.len(), |len, line| {
+        first[..len]
+            .char_indices()
+            .zip(lead(line).chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(lead(line).len()), |((i, _), _)| i)
+    });
+    &first[..common]
+}
+
+/// Gemeinsame führende Leerzeichen/Tabs aller nicht-leeren Zeilen.
+fn common_indentation(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(|common, indent| {
+            let len = common
+                .char_indices()
+                .zip(indent.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(indent.len()), |((i, _), _)| i);
+            &common[..len]
         })
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Ersetzt Tabs durch Leerzeichen bis zum nächsten Tabstopp (Spalten nach Anzeigebreite).
+fn expand_tabs(line: &str, tabstop: usize) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = tabstop - column % tabstop;
+            result.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            result.push(c);
+            column += c.width().unwrap_or(0);
+        }
     }
+    result
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn round_trip(text: &str) -> String {
+        let (normalized, endings) = TextProcessor::normalize_line_endings(text);
+        let body = normalized.strip_suffix('\n').unwrap_or(&normalized);
+        TextProcessor::restore_line_endings(body, &endings)
+    }
 
-    
+    #[test]
+    fn restore_keeps_style_and_trailing_newline() {
+        for text in ["a\nb\n", "a\r\nb\r\n", "a\rb\r", "a\r\nb", "a", ""] {
+            assert_eq!(round_trip(text), text, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn restore_uses_dominant_ending_for_mixed_input() {
+        assert_eq!(round_trip("a\r\nb\r\nc\nd"), "a\r\nb\r\nc\r\nd");
+    }
+
+    #[test]
+    fn remove_matches_normalized_lines_but_keeps_originals() {
+        let options = NormalizeOptions {
+            whitespace: true,
+            ..Default::default()
+        };
+        let text = "a\u{00A0}b\na b\nfoo\u{3000}\u{3000}bar";
+        let op = Operation::untracked();
+        let deduped =
+            TextProcessor::remove_lines_internal(text, "", "duplicates", true, false, false, Some(&options), &op).unwrap();
+        assert_eq!(deduped.result_text, "a\u{00A0}b\nfoo\u{3000}\u{3000}bar");
+
+        let removed =
+            TextProcessor::remove_lines_internal(text, "foo bar", "containing", true, false, false, Some(&options), &op)
+                .unwrap();
+        assert_eq!(removed.result_text, "a\u{00A0}b\na b");
+    }
+
+    #[test]
+    fn restore_skips_trailing_ending_for_empty_result() {
+        let endings = TextProcessor::detect_line_endings("x\r\n");
+        assert_eq!(TextProcessor::restore_line_endings("", &endings), "");
+        assert_eq!(TextProcessor::restore_line_endings("y\nz", &endings), "y\r\nz\r\n");
+    }
 }
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+/// Längere Zeilen werden in `LineInfo` gekürzt, damit das Ergebnis klein bleibt.
+const MAX_PREVIEW: usize = 200;
+
+/// Alles darüber landet in der letzten Histogramm-Klasse.
+const MAX_BUCKETS: usize = 50;
+
+/// Häufige deutsche und englische Füllwörter (kleingeschrieben).
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "all", "an", "and", "are", "as", "at", "be", "but", "by", "can", "do", "for", "from", "has",
+    "have", "he", "her", "his", "i", "if", "in", "is", "it", "its", "me", "my", "no", "not", "of", "on", "or",
+    "our", "she", "so", "that", "the", "their", "them", "there", "they", "this", "to", "was", "we", "were",
+    "what", "which", "will", "with", "you", "your", "aber", "als", "am", "auch", "auf", "aus", "bei",
+    "das", "dass", "dem", "den", "der", "des", "die", "ein", "eine", "einem", "einen", "einer", "er", "es",
+    "für", "hat", "ich", "im", "ist", "mit", "nach", "nicht", "noch", "oder", "sich", "sie", "sind",
+    "und", "von", "wie", "zu",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatsOptions {
+    /// Groß-/Kleinschreibung für die Häufigkeitstabellen ignorieren
+    pub casefold: bool,
+    /// Eingebaute deutsche/englische Stoppwörter ausfiltern
+    pub stopwords: bool,
+    /// Zusätzliche Stoppwörter
+    pub extra_stopwords: Vec<String>,
+    /// Einträge mit weniger Vorkommen erscheinen nicht in den Tabellen
+    pub min_count: usize,
+    /// Länge der N-Gramme (Wörter); unter 2 keine N-Gramm-Tabelle
+    pub ngram: usize,
+    /// Maximale Anzahl Einträge pro Tabelle
+    pub top: usize,
+    /// Breite der Histogramm-Klassen in Zeichen
+    pub bucket_size: usize,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self {
+            casefold: true,
+            stopwords: false,
+            extra_stopwords: Vec::new(),
+            min_count: 1,
+            ngram: 2,
+            top: 50,
+            bucket_size: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineInfo {
+    pub line: usize,
+    /// Länge in Graphemen (so, wie der Nutzer Zeichen wahrnimmt)
+    pub length: usize,
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBucket {
+    /// Inklusive Untergrenze
+    pub from: usize,
+    /// Inklusive Obergrenze
+    pub to: usize,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermCount {
+    pub term: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub lines: usize,
+    pub empty_lines: usize,
+    pub words: usize,
+    /// Verschiedene Wörter ohne Stoppwörter
+    pub unique_words: usize,
+    pub characters: usize,
+    pub graphemes: usize,
+    pub bytes: usize,
+    pub longest_line: Option<LineInfo>,
+    /// Kürzeste nicht-leere Zeile
+    pub shortest_line: Option<LineInfo>,
+    pub average_line_length: f64,
+    pub line_length_histogram: Vec<HistogramBucket>,
+    pub word_frequencies: Vec<TermCount>,
+    pub ngram_frequencies: Vec<TermCount>,
+}
+
+fn line_info(index: usize, line: &str, length: usize) -> LineInfo {
+    let preview = match line.grapheme_indices(true).nth(MAX_PREVIEW) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line.to_string(),
+    };
+    LineInfo {
+        line: index + 1,
+        length,
+        preview,
+    }
+}
+
+/// Häufigste Einträge zuerst, bei Gleichstand alphabetisch.
+fn frequency_table(counts: HashMap<String, usize>, min_count: usize, top: usize) -> Vec<TermCount> {
+    let mut table: Vec<TermCount> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .map(|(term, count)| TermCount { term, count })
+        .collect();
+    table.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    table.truncate(top);
+    table
+}
+
+/// Zählt Zeilen, Wörter, Zeichen, Grapheme und Bytes und erstellt Wort- und N-Gramm-Häufigkeiten.
+/// Stoppwörter zählen bei `words` mit, fehlen aber in den Tabellen; N-Gramme mit Stoppwort entfallen.
+pub fn analyze_text(text: &str, options: &StatsOptions, op: &Operation) -> Result<ProcessedText<TextStats>, OperationError> {
+    let mut collector = MetricsCollector::start("analyze_text", text.len());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    let mut stop_words: HashSet<String> = options.extra_stopwords.iter().map(|w| w.to_lowercase()).collect();
+    if options.stopwords {
+        stop_words.extend(STOP_WORDS.iter().map(|w| w.to_string()));
+    }
+    let is_stop_word = |word: &str| !stop_words.is_empty() && stop_words.contains(&word.to_lowercase());
+    collector.phase("parse");
+
+    let mut words = 0;
+    let mut empty_lines = 0;
+    let mut total_length = 0;
+    let mut lengths: Vec<usize> = Vec::with_capacity(total_lines);
+    let mut longest: Option<LineInfo> = None;
+    let mut shortest: Option<LineInfo> = None;
+    let mut word_counts: HashMap<String, usize> = HashMap::new();
+    let mut ngram_counts: HashMap<String, usize> = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+
+        let length = line.graphemes(true).count();
+        total_length += length;
+        lengths.push(length);
+        if line.trim().is_empty() {
+            empty_lines += 1;
+        } else if shortest.as_ref().is_none_or(|s| length < s.length) {
+            shortest = Some(line_info(index, line, length));
+        }
+        if longest.as_ref().is_none_or(|l| length > l.length) {
+            longest = Some(line_info(index, line, length));
+        }
+
+        let line_words: Vec<String> = line
+            .unicode_words()
+            .map(|w| if options.casefold { w.to_lowercase() } else { w.to_string() })
+            .collect();
+        words += line_words.len();
+
+        for word in line_words.iter().filter(|w| !is_stop_word(w)) {
+            *word_counts.entry(word.clone()).or_default() += 1;
+        }
+        if options.ngram >= 2 {
+            for window in line_words.windows(options.ngram) {
+                if !window.iter().any(|w| is_stop_word(w)) {
+                    *ngram_counts.entry(window.join(" ")).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    let bucket_size = options.bucket_size.max(1);
+    let mut histogram: Vec<HistogramBucket> = Vec::new();
+    for &length in &lengths {
+        let bucket = (length / bucket_size).min(MAX_BUCKETS - 1);
+        while histogram.len() <= bucket {
+            let from = histogram.len() * bucket_size;
+            histogram.push(HistogramBucket { from, to: from + bucket_size - 1, count: 0 });
+        }
+        histogram[bucket].count += 1;
+    }
+    if let (Some(last), Some(max)) = (histogram.last_mut(), lengths.iter().max()) {
+        last.to = last.to.max(*max);
+    }
+    collector.phase("process");
+
+    let unique_words = word_counts.len();
+    let stats = TextStats {
+        lines: total_lines,
+        empty_lines,
+        words,
+        unique_words,
+        characters: text.chars().count(),
+        graphemes: text.graphemes(true).count(),
+        bytes: text.len(),
+        longest_line: longest,
+        shortest_line: shortest,
+        average_line_length: if total_lines == 0 { 0.0 } else { total_length as f64 / total_lines as f64 },
+        line_length_histogram: histogram,
+        word_frequencies: frequency_table(word_counts, options.min_count, options.top),
+        ngram_frequencies: frequency_table(ngram_counts, options.min_count, options.top),
+    };
+    collector.phase("serialize");
+    let metrics = collector.finish(0);
+
+    Ok(ProcessedText {
+        result_text: stats,
+        removed_lines: 0,
+        metrics,
+    })
+}
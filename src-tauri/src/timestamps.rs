@@ -0,0 +1,358 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone};
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+lazy_static! {
+    /// Reihenfolge ist wichtig: längere/spezifischere Formate zuerst, damit z.B. RFC 2822 nicht nur teilweise erkannt wird.
+    static ref TIMESTAMP: Regex = Regex::new(concat!(
+        // RFC 2822: `Tue, 02 Jan 2024 10:00:00 +0000`
+        r"(?:\b(?:Mon|Tue|Wed|Thu|Fri|Sat|Sun), )?\b\d{1,2} (?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) \d{4} \d{2}:\d{2}(?::\d{2})? (?:[+-]\d{4}|GMT|UT|Z)",
+        // ISO 8601 mit optionaler Zeit, Nachkommastellen und Offset
+        r"|\b\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?: ?(?:Z|[+-]\d{2}:?\d{2})\b)?)?",
+        // Apache/nginx Access-Log: `02/Jan/2024:10:00:00 +0000`
+        r"|\b\d{2}/(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}",
+        // nginx Error-Log: `2024/01/02 10:00:00`
+        r"|\b\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}",
+        // Syslog ohne Jahr: `Jan  2 10:00:00`
+        r"|\b(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) +\d{1,2} \d{2}:\d{2}:\d{2}",
+    ))
+    .unwrap();
+    /// Unix-Zeit in Sekunden (optional mit Nachkommastellen) oder Millisekunden, 2001 bis 2033
+    static ref EPOCH: Regex = Regex::new(r"(?<![\d.])1\d{9}(?:\d{3}|\.\d{1,9})?(?![\d.])").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TimestampFormat {
+    /// Zeitstempel bleiben wie sie sind (nur Sortieren/Filtern); mit `timezone` wird ISO 8601 geschrieben
+    #[default]
+    Keep,
+    Iso8601,
+    Rfc2822,
+    /// Unix-Zeit in Sekunden
+    Epoch,
+    EpochMillis,
+    /// strftime-Muster aus `pattern`, z.B. `%d.%m.%Y %H:%M`
+    Custom,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimestampOptions {
+    pub format: TimestampFormat,
+    pub pattern: String,
+    /// Zielzeitzone: `utc`, `local` oder ein Offset wie `+02:00`; leer = Offset des Zeitstempels behalten
+    pub timezone: String,
+    /// Zeitzone für Zeitstempel ohne Offset (z.B. Syslog)
+    pub assumetimezone: String,
+    /// Alleinstehende 10- bzw. 13-stellige Zahlen als Unix-Zeit erkennen. Standardmäßig aus,
+    /// weil sonst Bestellnummern, Telefonnummern oder Byte-Zahlen als Datum umgeschrieben würden
+    pub epoch: bool,
+    /// Zeilen nach ihrem (ersten) Zeitstempel sortieren
+    pub sort: bool,
+    pub descending: bool,
+    /// Nur Zeilen in diesem Zeitfenster behalten (inklusive)
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl Default for TimestampOptions {
+    fn default() -> Self {
+        Self {
+            format: TimestampFormat::Keep,
+            pattern: String::new(),
+            timezone: String::new(),
+            assumetimezone: "local".to_string(),
+            epoch: false,
+            sort: false,
+            descending: false,
+            from: None,
+            to: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Zone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// `utc`/`z`/`gmt`, `local` oder `+02:00`, `+0200`, `-5`; leer ergibt `None`.
+    fn parse(value: &str) -> Result<Option<Zone>, String> {
+        let value = value.trim();
+        let zone = match value.to_lowercase().as_str() {
+            "" => return Ok(None),
+            "utc" | "z" | "gmt" => Zone::Utc,
+            "local" | "lokal" => Zone::Local,
+            _ => {
+                let invalid = || format!("⚠ Ungültige Zeitzone '{}'", value);
+                let (sign, rest) = if let Some(rest) = value.strip_prefix('+') {
+                    (1, rest)
+                } else if let Some(rest) = value.strip_prefix('-') {
+                    (-1, rest)
+                } else {
+                    return Err(invalid());
+                };
+                let digits = rest.replace(':', "");
+                if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                let (hours, minutes) = match digits.len() {
+                    1 | 2 => (digits.parse::<i32>().unwrap_or(0), 0),
+                    len => (digits[..len - 2].parse::<i32>().unwrap_or(0), digits[len - 2..].parse::<i32>().unwrap_or(0)),
+                };
+                let offset = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)?;
+                Zone::Fixed(offset)
+            }
+        };
+        Ok(Some(zone))
+    }
+
+    fn resolve(self, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Zone::Utc => Some(naive.and_utc().fixed_offset()),
+            Zone::Local => Local.from_local_datetime(&naive).earliest().map(|dt| dt.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(&naive).single(),
+        }
+    }
+
+    fn convert(self, dt: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Utc => dt.to_utc().fixed_offset(),
+            Zone::Local => dt.with_timezone(&Local).fixed_offset(),
+            Zone::Fixed(offset) => dt.with_timezone(&offset),
+        }
+    }
+}
+
+/// Liest einen einzelnen Zeitstempel (ISO 8601, RFC 2822, Log-Formate, Unix-Zeit);
+/// Werte ohne Offset werden in `assume` interpretiert, Syslog-Zeiten ohne Jahr bekommen das aktuelle Jahr.
+pub(crate) fn parse_timestamp(value: &str, assume: Zone) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
+        return Some(dt);
+    }
+    // Java/Python-Logger schreiben Millisekunden mit Komma
+    let value = &match value.starts_with(|c: char| c.is_ascii_digit()) {
+        true => value.replacen(',', ".", 1),
+        false => value.to_string(),
+    };
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt);
+    }
+    for format in [
+        "%d/%b/%Y:%H:%M:%S %z",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f %z",
+        "%Y-%m-%dT%H:%M%z",
+        "%Y-%m-%d %H:%M%z",
+    ] {
+        if let Ok(dt) = DateTime::parse_from_str(value, format) {
+            return Some(dt);
+        }
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+            return assume.resolve(naive);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return assume.resolve(date.and_hms_opt(0, 0, 0)?);
+    }
+    let with_year = format!("{} {}", Local::now().year(), value.split_whitespace().collect::<Vec<_>>().join(" "));
+    if let Ok(naive) = NaiveDateTime::parse_from_str(&with_year, "%Y %b %d %H:%M:%S") {
+        return assume.resolve(naive);
+    }
+    parse_epoch(value)
+}
+
+/// Unix-Zeit in Sekunden (auch mit Nachkommastellen), Millisekunden oder Mikrosekunden (journald).
+fn parse_epoch(value: &str) -> Option<DateTime<FixedOffset>> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if whole.len() < 9 || !whole.chars().all(|c| c.is_ascii_digit()) || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let number: i64 = whole.parse().ok()?;
+    let micros = match whole.len() {
+        ..=11 => {
+            let fraction: i64 = format!("{:0<6}", &fraction[..fraction.len().min(6)]).parse().ok()?;
+            number.checked_mul(1_000_000)?.checked_add(fraction)?
+        }
+        12..=14 => number.checked_mul(1_000)?,
+        _ => number,
+    };
+    DateTime::from_timestamp_micros(micros).map(|dt| dt.fixed_offset())
+}
+
+fn format_timestamp(dt: DateTime<FixedOffset>, format: TimestampFormat, pattern: &str) -> String {
+    match format {
+        TimestampFormat::Keep | TimestampFormat::Iso8601 => dt.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        TimestampFormat::Rfc2822 => dt.to_rfc2822(),
+        TimestampFormat::Epoch => dt.timestamp().to_string(),
+        TimestampFormat::EpochMillis => dt.timestamp_millis().to_string(),
+        TimestampFormat::Custom => dt.format(pattern).to_string(),
+    }
+}
+
+/// Alle erkannten Zeitstempel einer Zeile als (Start, Ende, Zeit), nach Position sortiert.
+fn find_timestamps(line: &str, assume: Zone, epoch: bool) -> Vec<(usize, usize, DateTime<FixedOffset>)> {
+    let mut found: Vec<(usize, usize, DateTime<FixedOffset>)> = TIMESTAMP
+        .find_iter(line)
+        .flatten()
+        .filter_map(|m| parse_timestamp(m.as_str(), assume).map(|dt| (m.start(), m.end(), dt)))
+        .collect();
+    if epoch {
+        for m in EPOCH.find_iter(line).flatten() {
+            if found.iter().all(|&(start, end, _)| m.end() <= start || m.start() >= end) {
+                if let Some(dt) = parse_epoch(m.as_str()) {
+                    found.push((m.start(), m.end(), dt));
+                }
+            }
+        }
+        found.sort_by_key(|&(start, _, _)| start);
+    }
+    found
+}
+
+/// Erkennt Zeitstempel in jeder Zeile, schreibt sie optional in ein anderes Format bzw. eine andere Zeitzone um
+/// und sortiert oder filtert die Zeilen nach dem ersten Zeitstempel. Zeilen ohne Zeitstempel (z.B. Stacktraces)
+/// gehören dabei zur letzten Zeile davor; Zeilen vor dem ersten Zeitstempel fallen bei einem Zeitfenster heraus.
+pub fn process_timestamps(
+    text: &str,
+    options: &TimestampOptions,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("process_timestamps", text.len());
+
+    let target = Zone::parse(&options.timezone)?;
+    let assume = Zone::parse(&options.assumetimezone)?.unwrap_or(Zone::Local);
+    if options.format == TimestampFormat::Custom {
+        if options.pattern.is_empty() {
+            return Err("⚠ Für ein eigenes Zeitformat wird ein Muster benötigt".to_string().into());
+        }
+        if StrftimeItems::new(&options.pattern).any(|item| matches!(item, Item::Error)) {
+            return Err(format!("⚠ Ungültiges Zeitformat '{}'", options.pattern).into());
+        }
+    }
+    let bound = |value: &Option<String>| -> Result<Option<DateTime<FixedOffset>>, String> {
+        match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            Some(v) => parse_timestamp(v, assume).map(Some).ok_or_else(|| format!("⚠ Unbekanntes Zeitformat '{}'", v)),
+            None => Ok(None),
+        }
+    };
+    let (from, to) = (bound(&options.from)?, bound(&options.to)?);
+    let rewrite = options.format != TimestampFormat::Keep || target.is_some();
+
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut current = None;
+    let mut entries: Vec<(Option<DateTime<FixedOffset>>, String)> = Vec::with_capacity(total_lines);
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        let found = find_timestamps(line, assume, options.epoch);
+        if let Some(&(_, _, dt)) = found.first() {
+            current = Some(dt);
+        }
+
+        let in_range = (from.is_none() && to.is_none())
+            || current.is_some_and(|time| from.is_none_or(|from| time >= from) && to.is_none_or(|to| time <= to));
+        if !in_range {
+            continue;
+        }
+
+        if !rewrite || found.is_empty() {
+            entries.push((current, line.to_string()));
+            continue;
+        }
+        let mut rewritten = String::with_capacity(line.len());
+        let mut last = 0;
+        for (start, end, dt) in found {
+            let dt = target.map_or(dt, |zone| zone.convert(dt));
+            rewritten.push_str(&line[last..start]);
+            rewritten.push_str(&format_timestamp(dt, options.format, &options.pattern));
+            last = end;
+        }
+        rewritten.push_str(&line[last..]);
+        entries.push((current, rewritten));
+    }
+    let removed_lines = total_lines - entries.len();
+
+    if options.sort {
+        op.check()?;
+        // Stabil, damit Folgezeilen hinter ihrer Zeile bleiben; Zeilen vor dem ersten Zeitstempel gelten als älteste
+        if options.descending {
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.0));
+        } else {
+            entries.sort_by_key(|entry| entry.0);
+        }
+        op.check()?;
+    }
+    collector.phase("process");
+
+    let result_text = entries.into_iter().map(|(_, line)| line).collect::<Vec<_>>().join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> Option<String> {
+        parse_timestamp(value, Zone::Utc).map(|dt| dt.to_rfc3339())
+    }
+
+    #[test]
+    fn parses_common_formats() {
+        assert_eq!(utc("2024-01-02T10:00:00+02:00").as_deref(), Some("2024-01-02T10:00:00+02:00"));
+        assert_eq!(utc("2024-01-02 10:00:00,123").as_deref(), Some("2024-01-02T10:00:00.123+00:00"));
+        assert_eq!(utc("02/Jan/2024:10:00:00 +0100").as_deref(), Some("2024-01-02T10:00:00+01:00"));
+        assert_eq!(utc("Tue, 02 Jan 2024 10:00:00 +0000").as_deref(), Some("2024-01-02T10:00:00+00:00"));
+        assert_eq!(utc("2024/01/02 10:00:00").as_deref(), Some("2024-01-02T10:00:00+00:00"));
+        assert_eq!(utc("2024-01-02").as_deref(), Some("2024-01-02T00:00:00+00:00"));
+        assert_eq!(utc("1704189600").as_deref(), Some("2024-01-02T10:00:00+00:00"));
+        assert_eq!(utc("1704189600123").as_deref(), Some("2024-01-02T10:00:00.123+00:00"));
+        assert_eq!(utc("kein Datum"), None);
+    }
+
+    #[test]
+    fn naive_values_use_assumed_zone() {
+        let zone = Zone::parse("+05:30").unwrap().unwrap();
+        let dt = parse_timestamp("2024-01-02 10:00:00", zone).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-01-02T10:00:00+05:30");
+    }
+
+    #[test]
+    fn zone_parsing() {
+        assert!(matches!(Zone::parse(""), Ok(None)));
+        assert!(matches!(Zone::parse(" UTC "), Ok(Some(Zone::Utc))));
+        assert!(matches!(Zone::parse("local"), Ok(Some(Zone::Local))));
+        for (value, seconds) in [("+02:00", 7200), ("+0200", 7200), ("-5", -18000), ("+0530", 19800)] {
+            let Ok(Some(Zone::Fixed(offset))) = Zone::parse(value) else {
+                panic!("{} nicht erkannt", value);
+            };
+            assert_eq!(offset.local_minus_utc(), seconds, "{}", value);
+        }
+        for value in ["Europe/Berlin", "+", "+12345", "+2x", "−02:00", "+99"] {
+            assert!(Zone::parse(value).is_err(), "{}", value);
+        }
+    }
+}
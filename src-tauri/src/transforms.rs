@@ -0,0 +1,409 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::{alphabet, DecodeError, Engine};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use quoted_printable::ParseMode;
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::{LineEndingInfo, ProcessedText, TextProcessor};
+
+/// Wie `encodeURIComponent`: alles außer `A-Z a-z 0-9 - _ . ~` wird kodiert.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// Beim Dekodieren ist Padding optional, Tokens (JWT usw.) lassen es meist weg.
+const STANDARD_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+const URL_SAFE_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Bytes pro Zeile im Hex-Dump.
+const HEX_DUMP_WIDTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformKind {
+    Base64,
+    Base64Url,
+    Url,
+    Hex,
+    HtmlEntities,
+    QuotedPrintable,
+    UnicodeEscape,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformDirection {
+    Encode,
+    Decode,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformResult {
+    pub text: String,
+    /// Nicht dekodierbare Stellen; betroffene Zeilen (bzw. der ganze Text) bleiben unverändert
+    pub errors: Vec<TransformError>,
+}
+
+impl ProcessedText<TransformResult> {
+    pub fn with_line_endings(mut self, endings: &LineEndingInfo, preserve: bool) -> Self {
+        if preserve {
+            self.result_text.text = TextProcessor::restore_line_endings(&self.result_text.text, endings);
+        }
+        self
+    }
+}
+
+/// Fehler beim Dekodieren, mit Zeile relativ zum dekodierten Abschnitt, falls bekannt.
+struct DecodeFailure {
+    line: Option<usize>,
+    message: String,
+}
+
+impl From<String> for DecodeFailure {
+    fn from(message: String) -> Self {
+        Self { line: None, message }
+    }
+}
+
+/// Kodiert oder dekodiert den ganzen Text bzw. jede Zeile einzeln (`perline`).
+/// Fehler brechen nicht ab, sondern werden mit Zeilennummer gemeldet.
+pub fn transform_text(
+    text: &str,
+    kind: TransformKind,
+    direction: TransformDirection,
+    perline: bool,
+    op: &Operation,
+) -> Result<ProcessedText<TransformResult>, OperationError> {
+    let mut collector = MetricsCollector::start("transform_text", text.len());
+
+    let lines: Vec<&str> = if perline { text.lines().collect() } else { vec![text] };
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut results: Vec<String> = Vec::with_capacity(total_lines);
+    let mut errors = Vec::new();
+    for (index, chunk) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        let outcome = match direction {
+            TransformDirection::Encode => Ok(encode(chunk, kind, perline)),
+            TransformDirection::Decode => decode(chunk, kind),
+        };
+        match outcome {
+            Ok(result) => results.push(result),
+            Err(failure) => {
+                let line = if perline { index + 1 } else { failure.line.unwrap_or(1) };
+                errors.push(TransformError { line, message: failure.message });
+                results.push(chunk.to_string());
+            }
+        }
+    }
+    collector.phase("process");
+
+    let result = results.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result.len());
+
+    Ok(ProcessedText {
+        result_text: TransformResult { text: result, errors },
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+fn encode(text: &str, kind: TransformKind, perline: bool) -> String {
+    match kind {
+        TransformKind::Base64 => STANDARD.encode(text),
+        TransformKind::Base64Url => URL_SAFE_NO_PAD.encode(text),
+        TransformKind::Url => utf8_percent_encode(text, URL_COMPONENT).to_string(),
+        TransformKind::Hex if perline => text.bytes().map(|b| format!("{:02x}", b)).collect(),
+        TransformKind::Hex => hex_dump(text.as_bytes()),
+        TransformKind::HtmlEntities => html_escape::encode_safe(text).into_owned(),
+        TransformKind::QuotedPrintable => quoted_printable::encode_to_str(text),
+        TransformKind::UnicodeEscape => encode_unicode_escapes(text),
+    }
+}
+
+fn decode(text: &str, kind: TransformKind) -> Result<String, DecodeFailure> {
+    match kind {
+        TransformKind::Base64 | TransformKind::Base64Url => {
+            let engine = if kind == TransformKind::Base64 { &STANDARD_LENIENT } else { &URL_SAFE_LENIENT };
+            decode_base64(text, engine)
+        }
+        TransformKind::Url => {
+            // Zeilenweise, damit ungültiges UTF-8 einer Zeile zugeordnet werden kann
+            for (index, line) in text.lines().enumerate() {
+                percent_decode_str(line).decode_utf8().map_err(|_| DecodeFailure {
+                    line: Some(index + 1),
+                    message: "⚠ Dekodierte Daten sind kein UTF-8-Text".to_string(),
+                })?;
+            }
+            Ok(percent_decode_str(text).decode_utf8_lossy().into_owned())
+        }
+        TransformKind::Hex => decode_hex(text),
+        TransformKind::HtmlEntities => Ok(html_escape::decode_html_entities(text).into_owned()),
+        TransformKind::QuotedPrintable => decode_quoted_printable(text),
+        TransformKind::UnicodeEscape => decode_unicode_escapes(text),
+    }
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String, DecodeFailure> {
+    String::from_utf8(bytes).map_err(|_| "⚠ Dekodierte Daten sind kein UTF-8-Text".to_string().into())
+}
+
+/// Zeilenumbrüche und Leerzeichen (z.B. bei umbrochenem Base64 aus E-Mails) werden ignoriert;
+/// die Fehlerposition wird auf die ursprüngliche Zeile zurückgerechnet.
+fn decode_base64(text: &str, engine: &GeneralPurpose) -> Result<String, DecodeFailure> {
+    let mut compact = String::with_capacity(text.len());
+    let mut line_starts = Vec::new();
+    for line in text.lines() {
+        line_starts.push(compact.len());
+        compact.extend(line.chars().filter(|c| !c.is_whitespace()));
+    }
+
+    let line_of = |offset: usize| line_starts.iter().rposition(|start| *start <= offset).map(|i| i + 1);
+    let bytes = engine.decode(&compact).map_err(|e| match e {
+        DecodeError::InvalidByte(offset, byte) => DecodeFailure {
+            line: line_of(offset),
+            message: format!("⚠ Ungültiges Base64-Zeichen '{}'", char::from(byte)),
+        },
+        DecodeError::InvalidLastSymbol(offset, _) => DecodeFailure {
+            line: line_of(offset),
+            message: "⚠ Ungültiges letztes Base64-Zeichen".to_string(),
+        },
+        // Längen- und Padding-Fehler zeigen sich erst am Ende
+        other => DecodeFailure {
+            line: Some(line_starts.len().max(1)),
+            message: format!("⚠ Ungültiges Base64: {}", other),
+        },
+    })?;
+    utf8(bytes)
+}
+
+/// Zeilenweise, damit Fehler eine Zeilennummer bekommen; `=` am Zeilenende ist ein weicher Umbruch.
+/// Anders als der strikte Modus des Crates werden auch LF-Zeilenenden akzeptiert;
+/// harte Umbrüche bekommen das vorherrschende Zeilenende der Eingabe.
+fn decode_quoted_printable(text: &str) -> Result<String, DecodeFailure> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let lines: Vec<&str> = text.lines().collect();
+    let endings = TextProcessor::detect_line_endings(text);
+    let ending = endings.dominant.map_or("\n", |ending| ending.as_str());
+
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.trim_end_matches([' ', '\t']);
+        let (content, soft_break) = match line.strip_suffix('=') {
+            Some(content) => (content, true),
+            None => (line, false),
+        };
+        // Leerzeichen vor einem weichen Umbruch gehören zum Text, der Decoder würde sie abschneiden
+        let encoded = content.trim_end_matches([' ', '\t']);
+        let decoded = quoted_printable::decode(encoded, ParseMode::Strict).map_err(|e| DecodeFailure {
+            line: Some(index + 1),
+            message: format!("⚠ Ungültiges Quoted-Printable: {}", e),
+        })?;
+        bytes.extend(decoded);
+        bytes.extend(&content.as_bytes()[encoded.len()..]);
+        if !soft_break && (index + 1 < lines.len() || endings.trailing_newline) {
+            bytes.extend(ending.as_bytes());
+        }
+    }
+    utf8(bytes)
+}
+
+/// `00000000  48 65 6c 6c 6f 0a              |Hello.|`
+fn hex_dump(bytes: &[u8]) -> String {
+    bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { char::from(b) } else { '.' })
+                .collect();
+            format!("{:08x}  {:<width$}  |{}|", index * HEX_DUMP_WIDTH, hex.join(" "), ascii, width = HEX_DUMP_WIDTH * 3 - 1)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Akzeptiert reines Hex (mit beliebigen Leerzeichen, optional `0x`-Präfixen) und Hex-Dumps im Format von `hex_dump`.
+fn decode_hex(text: &str) -> Result<String, DecodeFailure> {
+    let mut bytes = Vec::new();
+    let mut pending: Option<u8> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let fail = |message: String| DecodeFailure { line: Some(index + 1), message };
+        let data = strip_dump_columns(line);
+
+        let mut rest = data;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+                if pending.is_none() {
+                    rest = after;
+                    continue;
+                }
+            }
+            rest = &rest[c.len_utf8()..];
+            if c.is_whitespace() || c == ',' {
+                continue;
+            }
+            let Some(nibble) = c.to_digit(16) else {
+                return Err(fail(format!("⚠ Ungültiges Hex-Zeichen '{}'", c)));
+            };
+            match pending.take() {
+                Some(high) => bytes.push(high << 4 | nibble as u8),
+                None => pending = Some(nibble as u8),
+            }
+        }
+    }
+
+    if pending.is_some() {
+        return Err(DecodeFailure {
+            line: Some(text.lines().count().max(1)),
+            message: "⚠ Ungerade Anzahl Hex-Ziffern".to_string(),
+        });
+    }
+    utf8(bytes)
+}
+
+/// Entfernt Offset-Spalte und ASCII-Spalte einer Hex-Dump-Zeile.
+fn strip_dump_columns(line: &str) -> &str {
+    let trimmed = line.trim();
+    let offset_len = trimmed.chars().take_while(char::is_ascii_hexdigit).count();
+    let is_dump = offset_len >= 6
+        && trimmed.ends_with('|')
+        && trimmed[offset_len..].starts_with([' ', ':']);
+    if !is_dump {
+        return line;
+    }
+    let data = trimmed[offset_len..].trim_start_matches(':');
+    match data.find('|') {
+        Some(ascii_start) => &data[..ascii_start],
+        None => data,
+    }
+}
+
+/// Alles außerhalb von ASCII als `\uXXXX` (über U+FFFF als Surrogatpaar, wie in JSON/JavaScript).
+fn encode_unicode_escapes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            result.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                result.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    result
+}
+
+/// Dekodiert `\uXXXX` (inkl. Surrogatpaaren) und `\u{X…}`; andere Backslashes bleiben stehen.
+fn decode_unicode_escapes(text: &str) -> Result<String, DecodeFailure> {
+    let mut result = String::with_capacity(text.len());
+
+    for (index, line) in text.split('\n').enumerate() {
+        let fail = |message: String| DecodeFailure { line: Some(index + 1), message };
+        if index > 0 {
+            result.push('\n');
+        }
+
+        let mut rest = line;
+        while let Some(position) = rest.find("\\u") {
+            result.push_str(&rest[..position]);
+            let after = &rest[position + 2..];
+
+            let (code, consumed) = if let Some(braced) = after.strip_prefix('{') {
+                let end = braced.find('}').ok_or_else(|| fail("⚠ Fehlendes '}' in \\u{…}".to_string()))?;
+                let code = u32::from_str_radix(&braced[..end], 16)
+                    .map_err(|_| fail(format!("⚠ Ungültige Escape-Sequenz \\u{{{}}}", &braced[..end])))?;
+                (code, end + 2)
+            } else {
+                let code = parse_hex4(after).ok_or_else(|| {
+                    fail(format!("⚠ Ungültige Escape-Sequenz \\u{}", after.chars().take(4).collect::<String>()))
+                })?;
+                // High Surrogate: das Low Surrogate muss direkt folgen
+                if (0xD800..0xDC00).contains(&code) {
+                    let low = after[4..]
+                        .strip_prefix("\\u")
+                        .and_then(parse_hex4)
+                        .filter(|low| (0xDC00..0xE000).contains(low))
+                        .ok_or_else(|| fail(format!("⚠ Unvollständiges Surrogatpaar \\u{:04x}", code)))?;
+                    (0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00), 10)
+                } else {
+                    (code, 4)
+                }
+            };
+
+            let c = char::from_u32(code).ok_or_else(|| fail(format!("⚠ Ungültiger Codepoint U+{:04X}", code)))?;
+            result.push(c);
+            rest = &after[consumed..];
+        }
+        result.push_str(rest);
+    }
+    Ok(result)
+}
+
+fn parse_hex4(text: &str) -> Option<u32> {
+    let digits = text.get(..4)?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [TransformKind; 7] = [
+        TransformKind::Base64,
+        TransformKind::Base64Url,
+        TransformKind::Url,
+        TransformKind::Hex,
+        TransformKind::HtmlEntities,
+        TransformKind::QuotedPrintable,
+        TransformKind::UnicodeEscape,
+    ];
+
+    fn run(text: &str, kind: TransformKind, direction: TransformDirection, perline: bool) -> TransformResult {
+        transform_text(text, kind, direction, perline, &Operation::untracked()).unwrap().result_text
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = "Grüße <a href=\"x?y=1&z=2\"> 🎉\nzweite Zeile = 100%\tTab";
+        for kind in KINDS {
+            for perline in [false, true] {
+                let encoded = run(text, kind, TransformDirection::Encode, perline);
+                let decoded = run(&encoded.text, kind, TransformDirection::Decode, perline);
+                assert!(decoded.errors.is_empty(), "{:?} {}: {:?}", kind, perline, decoded.errors);
+                assert_eq!(decoded.text, text, "{:?} perline={}", kind, perline);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_errors_keep_the_line() {
+        let result = run("aGFsbG8=\n!!!\nd2VsdA==", TransformKind::Base64, TransformDirection::Decode, true);
+        assert_eq!(result.text, "hallo\n!!!\nwelt");
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].line, 2);
+    }
+}
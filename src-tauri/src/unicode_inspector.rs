@@ -0,0 +1,296 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+use crate::metrics::MetricsCollector;
+use crate::operations::{Operation, OperationError};
+use crate::text_processor::ProcessedText;
+
+/// Bei sehr vielen Funden (z.B. kompletter Text in Kyrillisch) werden nur so viele einzeln gemeldet.
+const MAX_FINDINGS: usize = 5000;
+
+/// Unsichtbare bzw. leicht übersehene Zeichen mit Namen. Alles mit `Some(' ')` gilt als Leerzeichen
+/// und wird beim Normalisieren zu ASCII-Space, der Rest wird entfernt.
+const INVISIBLE: [(char, &str, Option<char>); 29] = [
+    ('\u{00A0}', "NO-BREAK SPACE", Some(' ')),
+    ('\u{00AD}', "SOFT HYPHEN", None),
+    ('\u{034F}', "COMBINING GRAPHEME JOINER", None),
+    ('\u{061C}', "ARABIC LETTER MARK", None),
+    ('\u{1680}', "OGHAM SPACE MARK", Some(' ')),
+    ('\u{180E}', "MONGOLIAN VOWEL SEPARATOR", None),
+    ('\u{2000}', "EN QUAD", Some(' ')),
+    ('\u{2001}', "EM QUAD", Some(' ')),
+    ('\u{2002}', "EN SPACE", Some(' ')),
+    ('\u{2003}', "EM SPACE", Some(' ')),
+    ('\u{2004}', "THREE-PER-EM SPACE", Some(' ')),
+    ('\u{2005}', "FOUR-PER-EM SPACE", Some(' ')),
+    ('\u{2006}', "SIX-PER-EM SPACE", Some(' ')),
+    ('\u{2007}', "FIGURE SPACE", Some(' ')),
+    ('\u{2008}', "PUNCTUATION SPACE", Some(' ')),
+    ('\u{2009}', "THIN SPACE", Some(' ')),
+    ('\u{200A}', "HAIR SPACE", Some(' ')),
+    ('\u{200B}', "ZERO WIDTH SPACE", None),
+    ('\u{200C}', "ZERO WIDTH NON-JOINER", None),
+    ('\u{200D}', "ZERO WIDTH JOINER", None),
+    ('\u{200E}', "LEFT-TO-RIGHT MARK", None),
+    ('\u{200F}', "RIGHT-TO-LEFT MARK", None),
+    ('\u{2028}', "LINE SEPARATOR", Some(' ')),
+    ('\u{2029}', "PARAGRAPH SEPARATOR", Some(' ')),
+    ('\u{202F}', "NARROW NO-BREAK SPACE", Some(' ')),
+    ('\u{205F}', "MEDIUM MATHEMATICAL SPACE", Some(' ')),
+    ('\u{2060}', "WORD JOINER", None),
+    ('\u{3000}', "IDEOGRAPHIC SPACE", Some(' ')),
+    ('\u{FEFF}', "ZERO WIDTH NO-BREAK SPACE (BOM)", None),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind {
+    Control,
+    Invisible,
+    Bidi,
+    Confusable,
+    NonAscii,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharFinding {
+    pub line: usize,
+    pub column: usize,
+    /// z.B. "U+00A0"
+    pub code_point: String,
+    pub character: String,
+    pub kind: FindingKind,
+    pub name: Option<&'static str>,
+    /// ASCII-Text, mit dem das Zeichen verwechselt werden kann (z.B. kyrillisches "а" → "a")
+    pub looks_like: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectionReport {
+    pub findings: Vec<CharFinding>,
+    pub total_findings: usize,
+    pub counts: BTreeMap<FindingKind, usize>,
+    pub lines_with_findings: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NormalizeOptions {
+    /// "nfc", "nfkc", "nfd" oder "nfkd"; ohne Angabe keine Unicode-Normalisierung
+    pub form: Option<String>,
+    /// Unicode-Leerzeichen werden zu ASCII-Space, mehrere Leerzeichen zu einem, unsichtbare Zeichen entfallen
+    pub whitespace: bool,
+    /// Verwechselbare Zeichen werden auf ihr ASCII-Gegenstück abgebildet
+    pub fold_confusables: bool,
+}
+
+fn invisible_entry(c: char) -> Option<(&'static str, Option<char>)> {
+    INVISIBLE.iter().find(|(ch, _, _)| *ch == c).map(|(_, name, replacement)| (*name, *replacement))
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// ASCII-Gegenstück laut Unicode-Confusables (UTS #39), falls das Zeichen wie ASCII aussieht.
+fn confusable_ascii(c: char) -> Option<String> {
+    if c.is_ascii() {
+        return None;
+    }
+    let folded: String = skeleton(c.encode_utf8(&mut [0; 4])).collect();
+    let decomposed: String = c.to_string().nfd().collect();
+    (folded != decomposed && !folded.is_empty() && folded.is_ascii()).then_some(folded)
+}
+
+fn classify(c: char) -> Option<(FindingKind, Option<&'static str>, Option<String>)> {
+    if c == '\t' {
+        return None;
+    }
+    if c.is_control() {
+        return Some((FindingKind::Control, None, None));
+    }
+    if c.is_ascii() {
+        return None;
+    }
+    if is_bidi_control(c) {
+        return Some((FindingKind::Bidi, None, None));
+    }
+    if let Some((name, _)) = invisible_entry(c) {
+        return Some((FindingKind::Invisible, Some(name), None));
+    }
+    if let Some(ascii) = confusable_ascii(c) {
+        return Some((FindingKind::Confusable, None, Some(ascii)));
+    }
+    Some((FindingKind::NonAscii, None, None))
+}
+
+/// Meldet alle Steuerzeichen, unsichtbaren, verwechselbaren und sonstigen Nicht-ASCII-Zeichen mit Position.
+pub fn inspect_characters(text: &str, op: &Operation) -> Result<ProcessedText<InspectionReport>, OperationError> {
+    let mut collector = MetricsCollector::start("inspect_characters", text.len());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut findings = Vec::new();
+    let mut total_findings = 0;
+    let mut counts: BTreeMap<FindingKind, usize> = BTreeMap::new();
+    let mut lines_with_findings = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        let mut found_in_line = false;
+
+        for (column, c) in line.chars().enumerate() {
+            let Some((kind, name, looks_like)) = classify(c) else { continue };
+            found_in_line = true;
+            total_findings += 1;
+            *counts.entry(kind).or_default() += 1;
+
+            if findings.len() < MAX_FINDINGS {
+                findings.push(CharFinding {
+                    line: index + 1,
+                    column: column + 1,
+                    code_point: format!("U+{:04X}", c as u32),
+                    character: c.to_string(),
+                    kind,
+                    name,
+                    looks_like,
+                });
+            }
+        }
+
+        if found_in_line {
+            lines_with_findings += 1;
+        }
+    }
+    collector.phase("process");
+
+    let metrics = collector.finish(0);
+
+    Ok(ProcessedText {
+        result_text: InspectionReport {
+            findings,
+            total_findings,
+            counts,
+            lines_with_findings,
+        },
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+/// Vereinheitlicht den Text, damit Dedupe und Diff optisch gleiche Zeilen auch als gleich erkennen.
+/// Reihenfolge: Leerzeichen/unsichtbare Zeichen, Confusables, dann die Unicode-Normalform.
+pub fn normalize_text(
+    text: &str,
+    options: &NormalizeOptions,
+    op: &Operation,
+) -> Result<ProcessedText<String>, OperationError> {
+    let mut collector = MetricsCollector::start("normalize_text", text.len());
+
+    let normalizer = LineNormalizer::new(options)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let total_lines = lines.len();
+    collector.phase("parse");
+
+    let mut normalized: Vec<String> = Vec::with_capacity(total_lines);
+    for (index, line) in lines.iter().enumerate() {
+        op.progress(index, total_lines)?;
+        normalized.push(normalizer.apply(line));
+    }
+    collector.phase("process");
+
+    let result_text = normalized.join("\n");
+    collector.phase("serialize");
+    let metrics = collector.finish(result_text.len());
+
+    Ok(ProcessedText {
+        result_text,
+        removed_lines: 0,
+        metrics,
+    })
+}
+
+/// Normalisiert einzelne Zeilen. Entfernen und Diff nutzen das nur für ihre Vergleichsschlüssel
+/// und geben den Originaltext aus.
+pub(crate) struct LineNormalizer {
+    options: NormalizeOptions,
+    form: Option<String>,
+}
+
+impl LineNormalizer {
+    pub(crate) fn new(options: &NormalizeOptions) -> Result<Self, String> {
+        let form = options.form.as_deref().map(str::to_lowercase);
+        if let Some(form) = form.as_deref() {
+            if !matches!(form, "nfc" | "nfkc" | "nfd" | "nfkd") {
+                return Err(format!("⚠ Unbekannte Normalform '{}'", form));
+            }
+        }
+        Ok(Self {
+            options: options.clone(),
+            form,
+        })
+    }
+
+    pub(crate) fn apply(&self, line: &str) -> String {
+        let options = &self.options;
+        let mut result = String::with_capacity(line.len());
+        let push = |result: &mut String, c: char| {
+            if !(options.whitespace && c == ' ' && result.ends_with(' ')) {
+                result.push(c);
+            }
+        };
+        for c in line.chars() {
+            if options.whitespace {
+                if is_bidi_control(c) {
+                    continue;
+                }
+                if let Some((_, replacement)) = invisible_entry(c) {
+                    replacement.into_iter().for_each(|c| push(&mut result, c));
+                    continue;
+                }
+            }
+            match options.fold_confusables.then(|| confusable_ascii(c)).flatten() {
+                Some(ascii) => ascii.chars().for_each(|c| push(&mut result, c)),
+                None => push(&mut result, c),
+            }
+        }
+
+        match self.form.as_deref() {
+            Some("nfc") => result.nfc().collect(),
+            Some("nfkc") => result.nfkc().collect(),
+            Some("nfd") => result.nfd().collect(),
+            Some("nfkd") => result.nfkd().collect(),
+            _ => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace_is_unified_and_collapsed() {
+        let options = NormalizeOptions {
+            whitespace: true,
+            ..Default::default()
+        };
+        let normalizer = LineNormalizer::new(&options).unwrap();
+        assert_eq!(normalizer.apply("a\u{3000}b \u{00A0} c\u{200B}d\te"), "a b cd\te");
+    }
+
+    #[test]
+    fn unknown_form_is_rejected() {
+        let options = NormalizeOptions {
+            form: Some("nfx".to_string()),
+            ..Default::default()
+        };
+        assert!(LineNormalizer::new(&options).is_err());
+    }
+}
@@ -11,7 +11,7 @@ export interface SaveAppSettings {
   fontSize: fontSize;
   showLineNumbers: boolean;
   showEmptyLines: boolean;
-  savedPatterns: string[];
+  savedPatterns: SavedPattern[];
 }
 
 export interface AppSettings {
@@ -161,7 +161,7 @@ type PermanentTypeMap = {
   fontSize: fontSize;
   showLineNumbers: boolean;
   showEmptyLines: boolean;
-  savedPatterns: string[];
+  savedPatterns: SavedPattern[];
 };
 
 // --- Runtime Getter & Setter ---
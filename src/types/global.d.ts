@@ -26,7 +26,16 @@ interface SaveAppSettings {
   fontSize: fontSize;
   showLineNumbers: boolean;
   showEmptyLines: boolean;
-  savedPatterns: string[];
+  savedPatterns: SavedPattern[];
+}
+
+interface SavedPattern {
+  name: string;
+  pattern: string;
+  flags: { regex: boolean; caseSensitive: boolean; multiline: boolean };
+  tags: string[];
+  description: string;
+  examples: { input: string; expectedMatches: string[] }[];
 }
 
 interface AppSettings {
@@ -92,9 +101,16 @@ type MyResultType<T> = T extends ProcessedText
 
 
 interface BackendMetrics {
+    command?: string,
+    timestamp?: string,
     execution_time_ms: number,
     memory_usage_kb: number,
     memory_usage_str: string,
+    peak_alloc_bytes?: number,
+    net_alloc_bytes?: number,
+    phases?: { name: string, time_ms: number }[],
+    input_bytes?: number,
+    output_bytes?: number,
 }
 
 interface ProcessedText<T> {